@@ -1,120 +1,270 @@
 use crate::configuration::{
-    FIRST_YEAR_EXTRA_TA_HOURS, FULL_TA_HOURS, GRADUATE_COURSE, LAB_INSTRUCTOR_ADJUSTMENT,
-    LAB_RATIO_DENOMINATOR, MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD, MIN_ENROLLMENT_FOR_TA_ALLOC_UG,
-    MIN_TA_THRESHOLD, MIN_UNIT_WEIGHT_FOR_1YE_ADJUSTMENT, UNDERGRADUATE_COURSE,
+    DELIVERY_MODE_ADJUSTMENTS, FIRST_YEAR_UPLIFT, FULL_TA_HOURS, GRADUATE_COURSE,
+    LAB_INSTRUCTOR_ADJUSTMENT, LAB_RATIO_DENOMINATOR, MENG_COURSE,
+    MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD, MIN_ENROLLMENT_FOR_TA_ALLOC_MENG,
+    MIN_ENROLLMENT_FOR_TA_ALLOC_PD, MIN_ENROLLMENT_FOR_TA_ALLOC_UG, MIN_TA_THRESHOLD,
+    PROFESSIONAL_DEV_COURSE, ROUNDING_POLICY, STANDARD_ASSESSMENTS, UNDERGRADUATE_COURSE,
+    UNIT_WEIGHT_BASELINE,
+};
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use crate::specialcases::{LAB_ONLY_COURSES, LAB_PROFILES, SPECIAL_CASES};
+use crate::types::AllocationType::LAB;
+use crate::types::CourseType::{FIRST_YEAR, GRAD, MENG, PROFESSIONAL_DEV, UNDERGRAD};
+use crate::types::{AllocationComponent, AllocationRule, CourseAllocation};
+use crate::types::{
+    Applicability, CalculationRule, Course, CourseType, DeliveryMode, DeliveryModeAdjustment,
+    LabOnlySource, LabParameters, RoundingMode, RoundingPolicy, RoundingScope, SpecialCase,
+    SupportUplift, UpliftFormula,
 };
-use crate::specialcases::{LAB_ONLY_COURSES, SPECIAL_CASES};
-use crate::types::AllocationType::{LAB, NON_LAB};
-use crate::types::CourseType::{FIRST_YEAR, GRAD, UNDERGRAD};
-use crate::types::{AllocationRule, CourseAllocation};
-use crate::types::{CalculationRule, Course, CourseType};
 
-pub fn calculate_ta_hours(c: &Course) -> CourseAllocation {
-    let mut lab_amount: f32 = 0.0;
+// Prints a line of the explanation and keeps it with the allocation for the reports.
+macro_rules! explain {
+    ($trace:expr, $($arg:tt)*) => {{
+        let line = format!($($arg)*);
+        println!("{}", line);
+        $trace.push(line);
+    }};
+}
 
-    let course_is_lab_only = check_if_lab_only(&c.name);
+pub fn calculate_ta_hours(c: &Course) -> CourseAllocation {
+    let mut trace: Vec<String> = Vec::new();
+    let mut lab_amount = Fixed::ZERO;
+
+    let (course_is_lab_only, lab_only_source) = determine_lab_only(c);
+    explain!(
+        trace,
+        "Lab only: {} (decided by {})",
+        if course_is_lab_only { "yes" } else { "no" },
+        lab_only_source
+    );
 
-    let course_type = determine_course_type(&c.name);
+    let course_type = resolve_course_type(c);
     let configuration_to_use = match course_type {
         FIRST_YEAR => UNDERGRADUATE_COURSE,
         UNDERGRAD => UNDERGRADUATE_COURSE,
         GRAD => GRADUATE_COURSE,
+        PROFESSIONAL_DEV => PROFESSIONAL_DEV_COURSE,
+        MENG => MENG_COURSE,
     };
     let min_enrol = match course_type {
         UNDERGRAD => MIN_ENROLLMENT_FOR_TA_ALLOC_UG,
         GRAD => MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD,
         FIRST_YEAR => MIN_ENROLLMENT_FOR_TA_ALLOC_UG,
+        PROFESSIONAL_DEV => MIN_ENROLLMENT_FOR_TA_ALLOC_PD,
+        MENG => MIN_ENROLLMENT_FOR_TA_ALLOC_MENG,
     };
 
     if c.enrollment < min_enrol {
-        println!(
+        explain!(
+            trace,
             "Course enrollment for {} of {} is below min threshold of {}; allocation will be 0.",
-            &c.name, c.enrollment, min_enrol
+            &c.name,
+            c.enrollment,
+            min_enrol
         );
         return CourseAllocation {
-            total: 0.0,
-            lab_amount: 0.0,
+            total: Fixed::ZERO,
+            lab_amount: Fixed::ZERO,
+            components: Vec::new(),
+            special_case_reason: None,
+            trace,
         };
     }
 
-    println!(
-        "Course {} ({} students) is considered type {:?} (unit weight {:.2}; lab sections: {})",
-        &c.name, c.enrollment, course_type, c.unit_weight, c.lab_sections
+    explain!(trace,
+        "Course {} ({} students) is considered type {:?} (unit weight {:.2}; lab sections: {}; delivery: {:?})",
+        &c.name, c.enrollment, course_type, c.unit_weight, c.lab_sections, c.delivery_mode()
     );
 
-    let mut total_ta_hours: f32 = 0.0;
+    let mut total_ta_hours = Fixed::ZERO;
+    let mut components: Vec<AllocationComponent> = Vec::new();
 
     let students_per_lab_section = if c.lab_sections == 0 {
-        0.0
+        Fixed::ZERO
     } else {
-        c.enrollment as f32 / (c.lab_sections as f32)
+        Fixed::from_int(c.enrollment) / Fixed::from_int(c.lab_sections)
+    };
+    let lab_parameters = determine_lab_parameters(c);
+    let instructor_adjustment = if lab_parameters.lab_instructor {
+        LAB_INSTRUCTOR_ADJUSTMENT
+    } else {
+        Fixed::ZERO
     };
     let tas_per_lab_section = if c.lab_sections == 0 {
-        0.0
+        Fixed::ZERO
     } else {
-        ((students_per_lab_section / LAB_RATIO_DENOMINATOR) - LAB_INSTRUCTOR_ADJUSTMENT).max(0.0)
+        ((students_per_lab_section / lab_parameters.students_per_ta) - instructor_adjustment)
+            .max(Fixed::ZERO)
     };
-    println!(
+    if c.lab_sections > 0 {
+        explain!(
+            trace,
+            "Lab parameters: {} students per TA (from {}); lab instructor: {} (from {})",
+            lab_parameters.students_per_ta,
+            lab_parameters.students_per_ta_source,
+            if lab_parameters.lab_instructor {
+                "yes"
+            } else {
+                "no"
+            },
+            lab_parameters.lab_instructor_source
+        );
+    }
+    explain!(
+        trace,
         "Students per LAB section: {:.2}; TAs per lab section {:.2}",
-        students_per_lab_section, tas_per_lab_section
+        students_per_lab_section,
+        tas_per_lab_section
     );
 
+    let assessments = c.assessment_profile(STANDARD_ASSESSMENTS);
+    if assessments != STANDARD_ASSESSMENTS {
+        explain!(trace,
+            "Assessments: {} midterm(s), {} final exam, {} assignment(s) (marking rates assume {}, {}, {})",
+            assessments.midterms,
+            if assessments.final_exam { "a" } else { "no" },
+            assessments.assignments,
+            STANDARD_ASSESSMENTS.midterms,
+            if STANDARD_ASSESSMENTS.final_exam { "a final" } else { "no final" },
+            STANDARD_ASSESSMENTS.assignments
+        );
+    }
+
     for allocation in configuration_to_use {
-        if c.lab_sections > 0 && allocation.alloc_type == NON_LAB {
-            continue;
-        }
-        if c.lab_sections == 0 && allocation.alloc_type == LAB {
-            continue;
-        }
-        if course_is_lab_only && allocation.alloc_type != LAB {
+        if !rule_applies(allocation.applies_to, c, course_is_lab_only, course_type) {
             continue;
         }
 
         let hours_to_add = match allocation.calc_rule {
             CalculationRule::PER_TERM => allocation.hours,
-            CalculationRule::PER_LEC_SECTION => allocation.hours * c.lec_sections as f32,
-            CalculationRule::PER_STUDENT => allocation.hours * c.enrollment as f32,
-            CalculationRule::PER_LAB => {
-                allocation.hours * c.lab_sections as f32 * tas_per_lab_section
+            CalculationRule::PER_LEC_SECTION => allocation.hours * c.lec_sections,
+            CalculationRule::PER_TUT_SECTION => allocation.hours * c.tutorial_sections(),
+            CalculationRule::PER_STUDENT => allocation.hours * c.enrollment,
+            CalculationRule::PER_LAB => allocation.hours * c.lab_sections * tas_per_lab_section,
+        };
+        let hours_to_add = match allocation.assessment {
+            Some(assessment) => {
+                let count = assessments.count(assessment);
+                if count == 0 {
+                    explain!(
+                        trace,
+                        "Skipping {} (course has no {:?})",
+                        allocation.name,
+                        assessment
+                    );
+                    continue;
+                }
+                scale_for_assessments(hours_to_add, count, STANDARD_ASSESSMENTS.count(assessment))
             }
+            None => hours_to_add,
+        };
+        let hours_to_add = match find_delivery_mode_adjustment(c.delivery_mode(), allocation.name) {
+            Some(adjustment) if adjustment.factor == Fixed::ZERO => {
+                explain!(
+                    trace,
+                    "Skipping {} for {:?} delivery ({})",
+                    allocation.name,
+                    adjustment.mode,
+                    adjustment.reason
+                );
+                continue;
+            }
+            Some(adjustment) => {
+                explain!(
+                    trace,
+                    "Scaling {} by {} for {:?} delivery ({})",
+                    allocation.name,
+                    adjustment.factor,
+                    adjustment.mode,
+                    adjustment.reason
+                );
+                hours_to_add * adjustment.factor
+            }
+            None => hours_to_add,
+        };
+        let hours_to_add = if allocation.scale_by_unit_weight {
+            let scaled_hours = scale_for_unit_weight(hours_to_add, c.unit_weight);
+            explain!(trace,
+                "Adding {:.2} hours for {} (Calculation Rule: {:?}; {:.2} scaled by unit weight {} against {})",
+                scaled_hours,
+                allocation.name,
+                allocation.calc_rule,
+                hours_to_add,
+                c.unit_weight,
+                UNIT_WEIGHT_BASELINE
+            );
+            scaled_hours
+        } else {
+            explain!(
+                trace,
+                "Adding {:.2} hours for {} (Calculation Rule: {:?})",
+                hours_to_add,
+                allocation.name,
+                allocation.calc_rule
+            );
+            hours_to_add
         };
-        println!(
-            "Adding {:.2} hours for {} (Calculation Rule: {:?})",
-            hours_to_add, allocation.name, allocation.calc_rule
-        );
         total_ta_hours += hours_to_add;
         if allocation.alloc_type == LAB {
             lab_amount += hours_to_add;
         }
-    }
-
-    if course_type == FIRST_YEAR && c.unit_weight >= MIN_UNIT_WEIGHT_FOR_1YE_ADJUSTMENT {
-        let adjustment_hours = c.unit_weight * 2.0 * FIRST_YEAR_EXTRA_TA_HOURS;
-        println!(
-            "Adding {} extra hours for 1YE course with unit weight >= {} ",
-            adjustment_hours, MIN_UNIT_WEIGHT_FOR_1YE_ADJUSTMENT
+        components.push(AllocationComponent {
+            name: allocation.name,
+            hours: hours_to_add,
+            calc_rule: allocation.calc_rule,
+        });
+    }
+
+    let uplift = FIRST_YEAR_UPLIFT;
+    if rule_applies(uplift.applies_to, c, course_is_lab_only, course_type)
+        && c.unit_weight >= uplift.min_unit_weight
+    {
+        let (hours_to_add, calc_rule) = uplift_hours(&uplift, c, total_ta_hours);
+        explain!(
+            trace,
+            "Adding {:.2} hours for {} ({:?}, unit weight {} against {})",
+            hours_to_add,
+            uplift.name,
+            uplift.formula,
+            c.unit_weight,
+            UNIT_WEIGHT_BASELINE
         );
-        total_ta_hours += adjustment_hours;
+        total_ta_hours += hours_to_add;
+        if uplift.alloc_type == LAB {
+            lab_amount += hours_to_add;
+        }
+        components.push(AllocationComponent {
+            name: uplift.name,
+            hours: hours_to_add,
+            calc_rule,
+        });
     }
 
-    println!(
+    explain!(
+        trace,
         "Total TA hours for {} is calculated at {:.2}.",
-        c.name, total_ta_hours
+        c.name,
+        total_ta_hours
     );
-    let ta_fraction = apply_rounding(total_ta_hours);
-    let lab_amount = apply_rounding(lab_amount);
+    let (ta_fraction, lab_amount) = round_allocation(total_ta_hours, lab_amount, ROUNDING_POLICY);
 
     if ta_fraction < MIN_TA_THRESHOLD {
-        println!(
+        explain!(
+            trace,
             "This is below the min threshold of {}, so the allocation will be 0.",
             MIN_TA_THRESHOLD
         );
         CourseAllocation {
-            total: 0.0,
-            lab_amount: 0.0,
+            total: Fixed::ZERO,
+            lab_amount: Fixed::ZERO,
+            components,
+            special_case_reason: None,
+            trace,
         }
     } else {
-        println!(
+        explain!(
+            trace,
             "This results in a TA allocation of {:.2} [Lab: {:.2}, Lecture {:.2}].",
             ta_fraction,
             lab_amount,
@@ -123,21 +273,129 @@ pub fn calculate_ta_hours(c: &Course) -> CourseAllocation {
         CourseAllocation {
             total: ta_fraction,
             lab_amount,
+            components,
+            special_case_reason: None,
+            trace,
         }
     }
 }
 
-pub fn apply_rounding(hours: f32) -> f32 {
-    let ta_fraction = hours / FULL_TA_HOURS;
-    (ta_fraction * 4.0).round() / 4.0
+// A percentage uplift is worked out from the hours so far, so it goes in the breakdown
+// as a flat PER_TERM amount.
+pub fn uplift_hours(
+    uplift: &SupportUplift,
+    c: &Course,
+    table_hours: Fixed,
+) -> (Fixed, CalculationRule) {
+    let (hours, calc_rule) = match uplift.formula {
+        UpliftFormula::PER_STUDENT(hours) => (hours * c.enrollment, CalculationRule::PER_STUDENT),
+        UpliftFormula::PER_SECTION(hours) => {
+            (hours * c.lec_sections, CalculationRule::PER_LEC_SECTION)
+        }
+        UpliftFormula::PERCENTAGE(percent) => (
+            table_hours * percent / Fixed::from_int(100),
+            CalculationRule::PER_TERM,
+        ),
+    };
+    (scale_for_unit_weight(hours, c.unit_weight), calc_rule)
 }
 
-fn determine_course_type(course_name: &str) -> CourseType {
-    let first_number = course_name.find(char::is_numeric).unwrap();
-    let course_first_number = course_name.chars().nth(first_number).unwrap();
-    let course_code_level = char::to_digit(course_first_number, 10).unwrap();
+pub fn find_delivery_mode_adjustment(
+    mode: DeliveryMode,
+    rule: &str,
+) -> Option<&'static DeliveryModeAdjustment> {
+    DELIVERY_MODE_ADJUSTMENTS
+        .iter()
+        .find(|a| a.mode == mode && a.rule == rule)
+}
+
+pub fn scale_for_unit_weight(hours: Fixed, unit_weight: Fixed) -> Fixed {
+    hours * unit_weight / UNIT_WEIGHT_BASELINE
+}
 
-    if course_code_level == 1 {
+pub fn rule_applies(
+    applies_to: Applicability,
+    c: &Course,
+    course_is_lab_only: bool,
+    course_type: CourseType,
+) -> bool {
+    match applies_to {
+        Applicability::ALWAYS => true,
+        Applicability::WITH_LABS => c.lab_sections > 0,
+        Applicability::WITHOUT_LABS => c.lab_sections == 0 && !course_is_lab_only,
+        Applicability::LAB_ONLY => course_is_lab_only,
+        Applicability::NOT_LAB_ONLY => !course_is_lab_only,
+        Applicability::COURSE_TYPES(types) => types.contains(&course_type),
+    }
+}
+
+// Marking rates are for the standard number of an assessment, so they scale with the actual count.
+pub fn scale_for_assessments(hours: Fixed, count: i32, standard_count: i32) -> Fixed {
+    if count == standard_count || standard_count == 0 {
+        hours
+    } else {
+        hours * count / Fixed::from_int(standard_count)
+    }
+}
+
+// The rounding is done on the hours, before dividing, so that the division's own rounding
+// to four places can't push a fraction over a halfway point first.
+pub fn round_ta_fraction(hours: Fixed, mode: RoundingMode) -> Fixed {
+    let round = |step: Fixed| hours.round_to(FULL_TA_HOURS * step) / FULL_TA_HOURS;
+    match mode {
+        RoundingMode::NEAREST_QUARTER => round(Fixed::new(0.25)),
+        RoundingMode::UP_TO_QUARTER => {
+            hours.ceil_to(FULL_TA_HOURS * Fixed::new(0.25)) / FULL_TA_HOURS
+        }
+        RoundingMode::NEAREST_TENTH => round(Fixed::new(0.1)),
+        RoundingMode::NO_ROUNDING => hours / FULL_TA_HOURS,
+    }
+}
+
+// Returns the (total, lab) TA fractions for the given hours under the rounding policy.
+pub fn round_allocation(
+    total_hours: Fixed,
+    lab_hours: Fixed,
+    policy: RoundingPolicy,
+) -> (Fixed, Fixed) {
+    let lab_amount = round_ta_fraction(lab_hours, policy.mode);
+    match policy.scope {
+        RoundingScope::TOTAL => (round_ta_fraction(total_hours, policy.mode), lab_amount),
+        RoundingScope::PER_COMPONENT => {
+            let lecture_amount = round_ta_fraction(total_hours - lab_hours, policy.mode);
+            (lecture_amount + lab_amount, lab_amount)
+        }
+    }
+}
+
+// The Type column, when it's there, always wins over the course code.
+pub fn resolve_course_type(c: &Course) -> CourseType {
+    c.course_type
+        .unwrap_or_else(|| determine_course_type(&c.code()))
+}
+
+// Returns a warning if the Type column says something different from the course code.
+// MENG on a grad code isn't a disagreement, since the code can't say MENG anyway.
+pub fn check_course_type_column(c: &Course) -> Option<String> {
+    let listed_type = c.course_type?;
+    let code_type = determine_course_type(&c.code());
+    if listed_type == code_type || (listed_type == MENG && code_type == GRAD) {
+        return None;
+    }
+    Some(format!(
+        "Course {} is listed as type {:?}, but its course code suggests {:?}; using {:?}.",
+        c.name, listed_type, code_type, listed_type
+    ))
+}
+
+// MEng offerings share their course codes with the research-stream grad courses,
+// so they can only be identified by the Type column.
+pub fn determine_course_type(code: &CourseCode) -> CourseType {
+    let course_code_level = code.level();
+
+    if code.suffix == "PD" {
+        PROFESSIONAL_DEV
+    } else if course_code_level == 1 {
         FIRST_YEAR
     } else if course_code_level < 6 {
         UNDERGRAD
@@ -146,63 +404,140 @@ fn determine_course_type(course_name: &str) -> CourseType {
     }
 }
 
+pub fn find_special_case(code: &CourseCode) -> Option<&'static SpecialCase> {
+    SPECIAL_CASES
+        .iter()
+        .find(|o| CourseCode::parse(o.course).as_ref() == Ok(code))
+}
+
 pub fn check_for_special_case(
     course: &Course,
-    original_ta_alloc: CourseAllocation,
+    mut original_ta_alloc: CourseAllocation,
 ) -> CourseAllocation {
-    let course_name_no_space = course.name.replace(" ", "");
-    let sc = SPECIAL_CASES
-        .iter()
-        .find(|o| o.course == course_name_no_space);
+    let sc = find_special_case(&course.code());
     if sc.is_none() {
         return original_ta_alloc;
     }
     let sc = sc.unwrap();
-    println!(
+    explain!(
+        original_ta_alloc.trace,
         "Found special case for course {} of type {:?}. Reason: {}",
-        course.name, sc.allocation_rule, sc.reason
+        course.name,
+        sc.allocation_rule,
+        sc.reason
     );
     let new_alloc = match sc.allocation_rule {
-        AllocationRule::NO_TA_ALLOC => 0.0,
+        AllocationRule::NO_TA_ALLOC => Fixed::ZERO,
         AllocationRule::MIN_ALLOC => original_ta_alloc.total.max(sc.allocation_amount),
         AllocationRule::MAX_ALLOC => original_ta_alloc.total.min(sc.allocation_amount),
-        AllocationRule::PER_SECTION => sc.allocation_amount * course.lec_sections as f32,
-        AllocationRule::PER_LAB_SECTION => sc.allocation_amount * course.lab_sections as f32,
+        AllocationRule::PER_SECTION => sc.allocation_amount * course.lec_sections,
+        AllocationRule::PER_LAB_SECTION => sc.allocation_amount * course.lab_sections,
         AllocationRule::FIXED => sc.allocation_amount,
     };
     if new_alloc != original_ta_alloc.total {
-        println!(
+        explain!(
+            original_ta_alloc.trace,
             "Overriding original TA allocation of {:.1} with {:.1}",
-            original_ta_alloc.total, new_alloc
+            original_ta_alloc.total,
+            new_alloc
         );
         // TODO: Fix this
         return CourseAllocation {
             total: new_alloc,
-            lab_amount: 0.0,
+            lab_amount: Fixed::ZERO,
+            components: original_ta_alloc.components,
+            special_case_reason: Some(sc.reason),
+            trace: original_ta_alloc.trace,
         };
     }
-    original_ta_alloc
+    CourseAllocation {
+        special_case_reason: Some(sc.reason),
+        ..original_ta_alloc
+    }
+}
+
+// Each lab setting comes from its input column if it's filled in, then from the course's
+// entry in LAB_PROFILES, and otherwise from the defaults in the configuration.
+pub fn determine_lab_parameters(c: &Course) -> LabParameters {
+    let profile = LAB_PROFILES
+        .iter()
+        .find(|p| CourseCode::parse(p.course).as_ref() == Ok(&c.code()));
+
+    let (students_per_ta, students_per_ta_source) = match (c.lab_students_per_ta, profile) {
+        (Some(ratio), _) => (ratio, "Lab Ratio column"),
+        (None, Some(p)) => (p.students_per_ta, p.reason),
+        (None, None) => (LAB_RATIO_DENOMINATOR, "default"),
+    };
+    let (lab_instructor, lab_instructor_source) = match (c.lab_instructor, profile) {
+        (Some(li), _) => (li, "Lab Instructor column"),
+        (None, Some(p)) => (p.lab_instructor, p.reason),
+        (None, None) => (true, "default"),
+    };
+    LabParameters {
+        students_per_ta,
+        students_per_ta_source,
+        lab_instructor,
+        lab_instructor_source,
+    }
 }
 
-pub fn check_if_lab_only(course_name: &str) -> bool {
-    let course_name_no_space = course_name.replace(" ", "");
-    LAB_ONLY_COURSES.iter().any(|o| *o == course_name_no_space)
+// The Lab Only column wins if it's filled in, then the LAB_ONLY_COURSES list,
+// then an L suffix on the course code (NE216L, for example).
+pub fn determine_lab_only(c: &Course) -> (bool, LabOnlySource) {
+    if let Some(lab_only) = c.lab_only {
+        return (lab_only, LabOnlySource::COLUMN);
+    }
+    let code = c.code();
+    if check_if_lab_only(&code) {
+        return (true, LabOnlySource::LIST);
+    }
+    if code.suffix == "L" {
+        return (true, LabOnlySource::SUFFIX);
+    }
+    (false, LabOnlySource::DEFAULT)
+}
+
+pub fn check_if_lab_only(code: &CourseCode) -> bool {
+    LAB_ONLY_COURSES
+        .iter()
+        .any(|o| CourseCode::parse(o).as_ref() == Ok(code))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::calculator::{
-        apply_rounding, calculate_ta_hours, check_for_special_case, check_if_lab_only,
-        determine_course_type,
+        calculate_ta_hours, check_course_type_column, check_for_special_case, check_if_lab_only,
+        determine_course_type, determine_lab_only, determine_lab_parameters, round_allocation,
+        round_ta_fraction, rule_applies, scale_for_assessments, scale_for_unit_weight,
+        uplift_hours,
+    };
+    use crate::configuration::{
+        DELIVERY_MODE_ADJUSTMENTS, GRADUATE_COURSE, MENG_COURSE, PROFESSIONAL_DEV_COURSE,
+        UNDERGRADUATE_COURSE,
+    };
+    use crate::coursecode::CourseCode;
+    use crate::fixed::Fixed;
+    use crate::types::AllocationType::LECTURE;
+    use crate::types::Applicability::{
+        ALWAYS, COURSE_TYPES, LAB_ONLY, NOT_LAB_ONLY, WITHOUT_LABS, WITH_LABS,
     };
-    use crate::types::Course;
-    use crate::types::CourseType::{FIRST_YEAR, GRAD, UNDERGRAD};
+    use crate::types::CourseType::{FIRST_YEAR, GRAD, MENG, PROFESSIONAL_DEV, UNDERGRAD};
+    use crate::types::DeliveryMode::{HYBRID, IN_PERSON, ONLINE};
+    use crate::types::RoundingMode::{NEAREST_QUARTER, NEAREST_TENTH, NO_ROUNDING, UP_TO_QUARTER};
+    use crate::types::RoundingScope::{PER_COMPONENT, TOTAL};
+    use crate::types::UpliftFormula::{PERCENTAGE, PER_SECTION, PER_STUDENT};
+    use crate::types::{CalculationRule, SupportUplift};
+    use crate::types::{Course, LabOnlySource, RoundingPolicy};
+
+    fn code(course_name: &str) -> CourseCode {
+        CourseCode::parse(course_name).unwrap()
+    }
 
     #[test]
     fn determine_course_type_finds_course_if_1ye() {
         let course_name = String::from("ECE 150");
 
-        let ct = determine_course_type(&course_name);
+        let ct = determine_course_type(&code(&course_name));
 
         assert_eq!(ct, FIRST_YEAR)
     }
@@ -211,7 +546,7 @@ mod tests {
     fn determine_course_type_finds_course_if_not_1ye() {
         let course_name = String::from("ECE252");
 
-        let ct = determine_course_type(&course_name);
+        let ct = determine_course_type(&code(&course_name));
 
         assert_eq!(ct, UNDERGRAD)
     }
@@ -220,11 +555,149 @@ mod tests {
     fn determine_course_type_finds_course_if_grad() {
         let course_name = String::from("NE-650");
 
-        let ct = determine_course_type(&course_name);
+        let ct = determine_course_type(&code(&course_name));
 
         assert_eq!(ct, GRAD)
     }
 
+    #[test]
+    fn determine_course_type_finds_pd_course_from_suffix() {
+        let course_name = String::from("ECE 6607PD");
+
+        let ct = determine_course_type(&code(&course_name));
+
+        assert_eq!(ct, PROFESSIONAL_DEV)
+    }
+
+    #[test]
+    fn pd_course_uses_its_own_rules() {
+        let c = Course {
+            name: String::from("ECE 6609PD"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 40,
+            lec_sections: 1,
+            lab_sections: 0,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+
+        let calculated_ta_fraction = calculate_ta_hours(&c);
+
+        // 2.0 * 40 + 24 + 0.5 * 40 = 124 hours
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(1.0));
+    }
+
+    #[test]
+    fn pd_course_below_its_threshold_gets_no_alloc() {
+        let c = Course {
+            name: String::from("ECE 6609PD"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 9,
+            lec_sections: 1,
+            lab_sections: 0,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+
+        let calculated_ta_fraction = calculate_ta_hours(&c);
+
+        assert_eq!(calculated_ta_fraction.total, Fixed::ZERO);
+    }
+
+    #[test]
+    fn pd_courses_we_run_keep_their_fixed_allocation() {
+        let c = Course {
+            name: String::from("ECE 6607PD"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 80,
+            lec_sections: 1,
+            lab_sections: 0,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+
+        let calculated_ta_fraction = calculate_ta_hours(&c);
+        let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
+
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(1.0));
+    }
+
+    #[test]
+    fn meng_course_uses_its_own_rules_and_threshold() {
+        let mut c = Course {
+            name: String::from("ECE 650"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 18,
+            lec_sections: 1,
+            lab_sections: 0,
+            unit_weight: Fixed::new(0.5),
+            course_type: Some(MENG),
+            ..Default::default()
+        };
+
+        // Enough for a GRAD course, but not for MENG
+        assert_eq!(calculate_ta_hours(&c).total, Fixed::ZERO);
+
+        // 0.5 * 120 + 1.25 * 120 + 12 + 0.1 * 120 = 234 hours
+        c.enrollment = 120;
+        assert_eq!(calculate_ta_hours(&c).total, Fixed::new(1.75));
+    }
+
+    #[test]
+    fn type_column_overrides_course_code() {
+        let c = Course {
+            name: String::from("ECE 457C"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 17,
+            lec_sections: 1,
+            lab_sections: 0,
+            unit_weight: Fixed::new(0.5),
+            course_type: Some(GRAD),
+            ..Default::default()
+        };
+
+        let calculated_ta_fraction = calculate_ta_hours(&c);
+
+        // Below the undergrad threshold but over the grad one
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.5));
+    }
+
+    #[test]
+    fn type_column_disagreeing_with_code_gives_warning() {
+        let c = Course {
+            name: String::from("ECE 457C"),
+            course_type: Some(GRAD),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check_course_type_column(&c).unwrap(),
+            "Course ECE 457C is listed as type GRAD, but its course code suggests UNDERGRAD; using GRAD."
+        );
+    }
+
+    #[test]
+    fn type_column_agreeing_with_code_gives_no_warning() {
+        let agrees = Course {
+            name: String::from("ECE 150"),
+            course_type: Some(FIRST_YEAR),
+            ..Default::default()
+        };
+        let meng = Course {
+            name: String::from("ECE 650"),
+            course_type: Some(MENG),
+            ..Default::default()
+        };
+        let no_column = Course {
+            name: String::from("ECE 650"),
+            ..Default::default()
+        };
+
+        assert_eq!(check_course_type_column(&agrees), None);
+        assert_eq!(check_course_type_column(&meng), None);
+        assert_eq!(check_course_type_column(&no_column), None);
+    }
+
     #[test]
     fn undergrad_course_with_zero_enrollment_gets_no_alloc() {
         let course_name = String::from("ECE 155");
@@ -234,13 +707,14 @@ mod tests {
             enrollment: 0,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -252,13 +726,14 @@ mod tests {
             enrollment: 19,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -270,13 +745,14 @@ mod tests {
             enrollment: 20,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.5);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.5));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -288,13 +764,14 @@ mod tests {
             enrollment: 344,
             lec_sections: 1,
             lab_sections: 3,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 5.5);
-        assert_eq!(calculated_ta_fraction.lab_amount, 3.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(5.5));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(3.0));
     }
 
     #[test]
@@ -306,13 +783,14 @@ mod tests {
             enrollment: 15,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.5);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.5));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -324,13 +802,14 @@ mod tests {
             enrollment: 29,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.5);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.5));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -342,13 +821,14 @@ mod tests {
             enrollment: 67,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 1.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(1.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -360,14 +840,15 @@ mod tests {
             enrollment: 200,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 0.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -379,15 +860,16 @@ mod tests {
             enrollment: 50,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
         // NE340L uses FIXED allocation of 2.5
-        assert_eq!(calculated_ta_fraction.total, 2.5);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(2.5));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -399,14 +881,15 @@ mod tests {
             enrollment: 1000,
             lec_sections: 1,
             lab_sections: 10,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 6.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(6.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -418,14 +901,15 @@ mod tests {
             enrollment: 5,
             lec_sections: 1,
             lab_sections: 10,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 8.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(8.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -437,14 +921,15 @@ mod tests {
             enrollment: 900,
             lec_sections: 2,
             lab_sections: 0,
-            unit_weight: 0.25,
+            unit_weight: Fixed::new(0.25),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 2.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(2.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -456,14 +941,15 @@ mod tests {
             enrollment: 148,
             lec_sections: 3,
             lab_sections: 8,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 6.0);
-        assert_eq!(calculated_ta_fraction.lab_amount, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(6.0));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(0.0));
     }
 
     #[test]
@@ -475,104 +961,575 @@ mod tests {
             enrollment: 200,
             lec_sections: 1,
             lab_sections: 6,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 2.75);
-        assert_eq!(calculated_ta_fraction.lab_amount, 1.25);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(2.75));
+        assert_eq!(calculated_ta_fraction.lab_amount, Fixed::new(1.25));
     }
 
     // Tests for apply_rounding function
     #[test]
     fn apply_rounding_rounds_to_nearest_quarter() {
         // 0 hours should be 0
-        let result = apply_rounding(0.0);
-        assert_eq!(result, 0.0);
+        let result = round_ta_fraction(Fixed::new(0.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.0));
     }
 
     #[test]
     fn apply_rounding_rounds_down_correctly() {
         // 32.5 hours = 32.5/130 = 0.25, round to 1, 1/4 = 0.25
-        let result = apply_rounding(32.5);
-        assert_eq!(result, 0.25);
+        let result = round_ta_fraction(Fixed::new(32.5), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.25));
     }
 
     #[test]
     fn apply_rounding_rounds_to_nearest_half() {
         // 65 hours = 65/130 = 0.5, round to 2, 2/4 = 0.5
-        let result = apply_rounding(65.0);
-        assert_eq!(result, 0.5);
+        let result = round_ta_fraction(Fixed::new(65.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.5));
     }
 
     #[test]
     fn apply_rounding_rounds_to_full() {
         // 130 hours = 130/130 = 1.0, round to 4, 4/4 = 1.0
-        let result = apply_rounding(130.0);
-        assert_eq!(result, 1.0);
+        let result = round_ta_fraction(Fixed::new(130.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(1.0));
     }
 
     #[test]
     fn apply_rounding_handles_decimal_values() {
         // 16.25 hours = 16.25/130 ≈ 0.125, round to 0, but actually rounds to 0.5
         // Let's test with a value that gives 0.25: 32.5/130 = 0.25, rounds to 1, 1/4 = 0.25
-        let result = apply_rounding(16.25);
-        assert_eq!(result, 0.25);
+        let result = round_ta_fraction(Fixed::new(16.25), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.25));
     }
 
     #[test]
     fn apply_rounding_handles_2p5_quarter_allocation() {
         // 97.5 hours = 97.5/130 = 0.75, round to 3, 3/4 = 0.75
-        let result = apply_rounding(97.5);
-        assert_eq!(result, 0.75);
+        let result = round_ta_fraction(Fixed::new(97.5), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.75));
     }
 
     #[test]
     fn apply_rounding_handles_large_values() {
         // 260 hours = 260/130 = 2.0, round to 8, 8/4 = 2.0
-        let result = apply_rounding(260.0);
-        assert_eq!(result, 2.0);
+        let result = round_ta_fraction(Fixed::new(260.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(2.0));
     }
 
     #[test]
     fn apply_rounding_rounds_up_at_threshold() {
         // 48.75 hours = 48.75/130 ≈ 0.375, round to 0, but let's check what we actually get
         // Let's use 65 hours to get 0.5: 65/130 = 0.5, round to 2, 2/4 = 0.5
-        let result = apply_rounding(65.0);
-        assert_eq!(result, 0.5);
+        let result = round_ta_fraction(Fixed::new(65.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.5));
+    }
+
+    #[test]
+    fn round_ta_fraction_up_to_quarter() {
+        // 33 hours / 130 ≈ 0.254, which is just over a quarter
+        assert_eq!(
+            round_ta_fraction(Fixed::new(33.0), UP_TO_QUARTER),
+            Fixed::new(0.5)
+        );
+        assert_eq!(
+            round_ta_fraction(Fixed::new(33.0), NEAREST_QUARTER),
+            Fixed::new(0.25)
+        );
+    }
+
+    #[test]
+    fn round_ta_fraction_up_to_quarter_rounds_the_hours_up() {
+        // 32.5001 / 130 is 0.2500008, which is over a quarter even though it's 0.2500 to
+        // four places
+        assert_eq!(
+            round_ta_fraction(Fixed::new(32.5001), UP_TO_QUARTER),
+            Fixed::new(0.5)
+        );
+        assert_eq!(
+            round_ta_fraction(Fixed::new(32.5), UP_TO_QUARTER),
+            Fixed::new(0.25)
+        );
+    }
+
+    #[test]
+    fn round_ta_fraction_rounds_once_at_the_boundary() {
+        // 16.2495 / 130 is 0.124996, just under an eighth; dividing first would give 0.1250
+        // and then round up to a quarter
+        assert_eq!(
+            round_ta_fraction(Fixed::new(16.2495), NEAREST_QUARTER),
+            Fixed::ZERO
+        );
+        assert_eq!(
+            round_ta_fraction(Fixed::new(16.25), NEAREST_QUARTER),
+            Fixed::new(0.25)
+        );
+        // 6.4999 / 130 is 0.049999, just under a twentieth
+        assert_eq!(
+            round_ta_fraction(Fixed::new(6.4999), NEAREST_TENTH),
+            Fixed::ZERO
+        );
+    }
+
+    #[test]
+    fn round_ta_fraction_nearest_tenth() {
+        // 91 hours / 130 = 0.7
+        assert_eq!(
+            round_ta_fraction(Fixed::new(91.0), NEAREST_TENTH),
+            Fixed::new(0.7)
+        );
+    }
+
+    #[test]
+    fn round_ta_fraction_no_rounding() {
+        // 91 hours / 130 = 0.7
+        assert_eq!(
+            round_ta_fraction(Fixed::new(91.0), NO_ROUNDING),
+            Fixed::new(0.7)
+        );
+    }
+
+    #[test]
+    fn round_allocation_on_total_rounds_total_and_lab() {
+        // 78 hours is 0.6 -> 0.5 total; 45.5 lab hours is 0.35 -> 0.25 lab
+        let policy = RoundingPolicy {
+            mode: NEAREST_QUARTER,
+            scope: TOTAL,
+        };
+        let (total, lab) = round_allocation(Fixed::new(78.0), Fixed::new(45.5), policy);
+
+        assert_eq!(total, Fixed::new(0.5));
+        assert_eq!(lab, Fixed::new(0.25));
+    }
+
+    #[test]
+    fn round_allocation_per_component_adds_rounded_parts() {
+        // 45.5 lab hours is 0.35 -> 0.25; 32.5 lecture hours is 0.25 -> 0.25
+        let policy = RoundingPolicy {
+            mode: NEAREST_QUARTER,
+            scope: PER_COMPONENT,
+        };
+        let (total, lab) = round_allocation(Fixed::new(78.0), Fixed::new(45.5), policy);
+
+        assert_eq!(total, Fixed::new(0.5));
+        assert_eq!(lab, Fixed::new(0.25));
+
+        let policy = RoundingPolicy {
+            mode: UP_TO_QUARTER,
+            scope: PER_COMPONENT,
+        };
+        let (total, lab) = round_allocation(Fixed::new(78.0), Fixed::new(45.5), policy);
+
+        assert_eq!(total, Fixed::new(0.75));
+        assert_eq!(lab, Fixed::new(0.5));
     }
 
     // Tests for check_if_lab_only function
     #[test]
     fn check_if_lab_only_identifies_lab_courses() {
-        let result = check_if_lab_only("ECE198");
-        assert_eq!(result, true);
+        let result = check_if_lab_only(&code("ECE198"));
+        assert!(result);
     }
 
     #[test]
     fn check_if_lab_only_identifies_lab_courses_with_space() {
-        let result = check_if_lab_only("ECE 198");
-        assert_eq!(result, true);
+        let result = check_if_lab_only(&code("ECE 198"));
+        assert!(result);
     }
 
     #[test]
     fn check_if_lab_only_identifies_non_lab_courses() {
-        let result = check_if_lab_only("ECE 150");
-        assert_eq!(result, false);
+        let result = check_if_lab_only(&code("ECE 150"));
+        assert!(!result);
     }
 
     #[test]
     fn check_if_lab_only_identifies_ne340l() {
-        let result = check_if_lab_only("NE 340L");
-        assert_eq!(result, true);
+        let result = check_if_lab_only(&code("NE 340L"));
+        assert!(result);
     }
 
     #[test]
     fn check_if_lab_only_identifies_non_existent_course_as_non_lab() {
-        let result = check_if_lab_only("XYZ999");
-        assert_eq!(result, false);
+        let result = check_if_lab_only(&code("XYZ999"));
+        assert!(!result);
+    }
+
+    #[test]
+    fn determine_lab_only_prefers_column_then_list_then_suffix() {
+        let column_says_no = Course {
+            name: String::from("ECE 198"),
+            lab_only: Some(false),
+            ..Default::default()
+        };
+        let listed = Course {
+            name: String::from("NE 455A"),
+            ..Default::default()
+        };
+        let suffix = Course {
+            name: String::from("NE 216L"),
+            ..Default::default()
+        };
+        let lecture = Course {
+            name: String::from("ECE 252"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            determine_lab_only(&column_says_no),
+            (false, LabOnlySource::COLUMN)
+        );
+        assert_eq!(determine_lab_only(&listed), (true, LabOnlySource::LIST));
+        assert_eq!(determine_lab_only(&suffix), (true, LabOnlySource::SUFFIX));
+        assert_eq!(
+            determine_lab_only(&lecture),
+            (false, LabOnlySource::DEFAULT)
+        );
+    }
+
+    #[test]
+    fn lab_only_decision_is_explained_below_min_enrollment() {
+        let c = Course {
+            name: String::from("NE 216L"),
+            enrollment: 5,
+            lab_sections: 1,
+            ..Default::default()
+        };
+
+        let allocation = calculate_ta_hours(&c);
+
+        assert_eq!(allocation.total, Fixed::ZERO);
+        assert!(allocation.trace.contains(&String::from(
+            "Lab only: yes (decided by the L suffix on the course code)"
+        )));
+    }
+
+    #[test]
+    fn lab_only_column_makes_course_lab_only() {
+        let c = Course {
+            name: String::from("ECE 299"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 100,
+            lec_sections: 1,
+            lab_sections: 4,
+            unit_weight: Fixed::new(0.5),
+            lab_only: Some(true),
+            ..Default::default()
+        };
+
+        let calculated_ta_fraction = calculate_ta_hours(&c);
+
+        assert_eq!(
+            calculated_ta_fraction.lab_amount,
+            calculated_ta_fraction.total
+        );
+        assert!(calculated_ta_fraction.total > Fixed::ZERO);
+    }
+
+    #[test]
+    fn determine_lab_parameters_prefers_columns_then_profile() {
+        let defaults = Course {
+            name: String::from("ECE 222"),
+            ..Default::default()
+        };
+        let profile = Course {
+            name: String::from("ECE 260"),
+            ..Default::default()
+        };
+        let columns = Course {
+            name: String::from("ECE 260"),
+            lab_students_per_ta: Some(Fixed::new(10.0)),
+            lab_instructor: Some(true),
+            ..Default::default()
+        };
+
+        let lp = determine_lab_parameters(&defaults);
+        assert_eq!(lp.students_per_ta, Fixed::new(15.0));
+        assert!(lp.lab_instructor);
+        assert_eq!(lp.students_per_ta_source, "default");
+
+        let lp = determine_lab_parameters(&profile);
+        assert_eq!(lp.students_per_ta, Fixed::new(15.0));
+        assert!(!lp.lab_instructor);
+        assert_eq!(lp.lab_instructor_source, "High power lab");
+
+        let lp = determine_lab_parameters(&columns);
+        assert_eq!(lp.students_per_ta, Fixed::new(10.0));
+        assert!(lp.lab_instructor);
+        assert_eq!(lp.students_per_ta_source, "Lab Ratio column");
+    }
+
+    #[test]
+    fn lab_parameters_change_lab_hours() {
+        let mut c = Course {
+            name: String::from("ECE 224"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 120,
+            lec_sections: 1,
+            lab_sections: 4,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+
+        // 30 students per section: 30 / 15 - 1 = 1 TA per section, 20 hours each
+        assert_eq!(calculate_ta_hours(&c).lab_amount, Fixed::new(0.5));
+
+        // 30 / 10 - 0 = 3 TAs per section
+        c.lab_students_per_ta = Some(Fixed::new(10.0));
+        c.lab_instructor = Some(false);
+        assert_eq!(calculate_ta_hours(&c).lab_amount, Fixed::new(1.75));
+    }
+
+    #[test]
+    fn tutorials_are_charged_per_tutorial_section() {
+        let mut c = Course {
+            name: String::from("ECE 140"),
+            instructor: "Example Instructor".to_string(),
+            enrollment: 400,
+            lec_sections: 2,
+            lab_sections: 0,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+        let one_per_lecture = calculate_ta_hours(&c);
+        let tutorials = |a: &crate::types::CourseAllocation| {
+            a.components
+                .iter()
+                .find(|x| x.calc_rule == crate::types::CalculationRule::PER_TUT_SECTION)
+                .unwrap()
+                .hours
+        };
+
+        c.tut_sections = Some(8);
+        let eight_tutorials = calculate_ta_hours(&c);
+
+        assert_eq!(tutorials(&one_per_lecture), Fixed::new(22.0));
+        assert_eq!(tutorials(&eight_tutorials), Fixed::new(88.0));
+    }
+
+    #[test]
+    fn standard_assessments_match_the_rule_table() {
+        let c = Course {
+            name: String::from("ECE 350"),
+            enrollment: 100,
+            lec_sections: 1,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+        let listed = Course {
+            midterms: Some(1),
+            final_exam: Some(true),
+            assignments: Some(4),
+            ..c
+        };
+
+        let hours: Fixed = calculate_ta_hours(&listed)
+            .components
+            .iter()
+            .map(|x| x.hours)
+            .sum();
+
+        // Same as with no assessment columns at all
+        assert_eq!(hours, Fixed::new(220.0));
+    }
+
+    #[test]
+    fn marking_follows_the_assessment_profile() {
+        let c = Course {
+            name: String::from("ECE 350"),
+            enrollment: 100,
+            lec_sections: 1,
+            unit_weight: Fixed::new(0.5),
+            midterms: Some(2),
+            final_exam: Some(false),
+            assignments: Some(2),
+            ..Default::default()
+        };
+
+        let allocation = calculate_ta_hours(&c);
+        let hours: Fixed = allocation.components.iter().map(|x| x.hours).sum();
+
+        // Midterm Marking doubles (+20), Final Marking is skipped (-33),
+        // Assignment Marking halves (-50)
+        assert_eq!(allocation.components.len(), 8);
+        assert_eq!(hours, Fixed::new(157.0));
+    }
+
+    #[test]
+    fn scale_for_assessments_is_proportional() {
+        assert_eq!(
+            scale_for_assessments(Fixed::new(100.0), 6, 4),
+            Fixed::new(150.0)
+        );
+        assert_eq!(
+            scale_for_assessments(Fixed::new(20.0), 1, 1),
+            Fixed::new(20.0)
+        );
+    }
+
+    #[test]
+    fn rule_applicability_conditions() {
+        let lab_course = Course {
+            name: String::from("ECE 250"),
+            lab_sections: 2,
+            ..Default::default()
+        };
+        let lecture_course = Course {
+            name: String::from("ECE 350"),
+            ..Default::default()
+        };
+
+        assert!(rule_applies(ALWAYS, &lab_course, true, UNDERGRAD));
+        assert!(rule_applies(WITH_LABS, &lab_course, false, UNDERGRAD));
+        assert!(!rule_applies(WITH_LABS, &lecture_course, false, UNDERGRAD));
+        assert!(rule_applies(
+            WITHOUT_LABS,
+            &lecture_course,
+            false,
+            UNDERGRAD
+        ));
+        assert!(!rule_applies(WITHOUT_LABS, &lab_course, false, UNDERGRAD));
+        // A lab-only course has labs even if no sections were entered
+        assert!(!rule_applies(
+            WITHOUT_LABS,
+            &lecture_course,
+            true,
+            UNDERGRAD
+        ));
+        assert!(rule_applies(LAB_ONLY, &lab_course, true, UNDERGRAD));
+        assert!(!rule_applies(NOT_LAB_ONLY, &lab_course, true, UNDERGRAD));
+        assert!(rule_applies(
+            COURSE_TYPES(&[FIRST_YEAR]),
+            &lecture_course,
+            false,
+            FIRST_YEAR
+        ));
+        assert!(!rule_applies(
+            COURSE_TYPES(&[FIRST_YEAR]),
+            &lecture_course,
+            false,
+            UNDERGRAD
+        ));
+    }
+
+    #[test]
+    fn uplift_formulas_scale_with_unit_weight() {
+        let c = Course {
+            name: String::from("ECE 105"),
+            enrollment: 100,
+            lec_sections: 2,
+            unit_weight: Fixed::new(1.0),
+            ..Default::default()
+        };
+        let uplift = |formula| SupportUplift {
+            name: "First Year Support",
+            formula,
+            alloc_type: LECTURE,
+            applies_to: COURSE_TYPES(&[FIRST_YEAR]),
+            min_unit_weight: Fixed::new(0.5),
+        };
+
+        // Unit weight 1.0 against a 0.5 baseline doubles everything
+        assert_eq!(
+            uplift_hours(&uplift(PER_STUDENT(Fixed::new(0.1))), &c, Fixed::ZERO),
+            (Fixed::new(20.0), CalculationRule::PER_STUDENT)
+        );
+        assert_eq!(
+            uplift_hours(&uplift(PER_SECTION(Fixed::new(5.0))), &c, Fixed::ZERO),
+            (Fixed::new(20.0), CalculationRule::PER_LEC_SECTION)
+        );
+        assert_eq!(
+            uplift_hours(&uplift(PERCENTAGE(Fixed::new(10.0))), &c, Fixed::new(200.0)),
+            (Fixed::new(40.0), CalculationRule::PER_TERM)
+        );
+    }
+
+    #[test]
+    fn scale_for_unit_weight_is_relative_to_baseline() {
+        assert_eq!(
+            scale_for_unit_weight(Fixed::new(11.0), Fixed::new(0.25)),
+            Fixed::new(5.5)
+        );
+        assert_eq!(
+            scale_for_unit_weight(Fixed::new(11.0), Fixed::new(0.5)),
+            Fixed::new(11.0)
+        );
+        assert_eq!(
+            scale_for_unit_weight(Fixed::new(11.0), Fixed::new(1.0)),
+            Fixed::new(22.0)
+        );
+    }
+
+    #[test]
+    fn online_courses_use_the_delivery_mode_adjustments() {
+        let hours = |delivery_mode| {
+            let c = Course {
+                name: String::from("ECE 350"),
+                enrollment: 100,
+                lec_sections: 1,
+                unit_weight: Fixed::new(0.5),
+                delivery_mode,
+                ..Default::default()
+            };
+            let allocation = calculate_ta_hours(&c);
+            let total: Fixed = allocation.components.iter().map(|x| x.hours).sum();
+            (allocation.components.len(), total)
+        };
+
+        assert_eq!(hours(None), (9, Fixed::new(220.0)));
+        assert_eq!(hours(Some(IN_PERSON)), (9, Fixed::new(220.0)));
+        // No Exam Proctoring (-17), twice the Office Hours Online (+17)
+        assert_eq!(hours(Some(ONLINE)), (8, Fixed::new(220.0)));
+        // One and a half times the Office Hours Online (+8.5)
+        assert_eq!(hours(Some(HYBRID)), (9, Fixed::new(228.5)));
+    }
+
+    #[test]
+    fn delivery_mode_adjustments_name_real_rules() {
+        let tables = [
+            UNDERGRADUATE_COURSE,
+            GRADUATE_COURSE,
+            PROFESSIONAL_DEV_COURSE,
+            MENG_COURSE,
+        ];
+
+        // A typo in a rule name would otherwise just never match anything
+        for adjustment in DELIVERY_MODE_ADJUSTMENTS {
+            assert!(
+                tables
+                    .iter()
+                    .any(|table| table.iter().any(|rule| rule.name == adjustment.rule)),
+                "No course type has a rule called '{}'",
+                adjustment.rule
+            );
+        }
+    }
+
+    #[test]
+    fn first_year_uplift_is_in_the_breakdown() {
+        let first_year = Course {
+            name: String::from("ECE 105"),
+            enrollment: 100,
+            lec_sections: 1,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+        let first_year_components = calculate_ta_hours(&first_year).components.len();
+        let second_year = Course {
+            name: String::from("ECE 205"),
+            ..first_year
+        };
+
+        assert_eq!(
+            first_year_components,
+            calculate_ta_hours(&second_year).components.len() + 1
+        );
     }
 
     // Additional edge case tests
@@ -585,12 +1542,13 @@ mod tests {
             enrollment: 20,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.5);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.5));
     }
 
     #[test]
@@ -602,12 +1560,13 @@ mod tests {
             enrollment: 15, // Minimum is 15, not 10
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.5);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.5));
     }
 
     #[test]
@@ -619,12 +1578,13 @@ mod tests {
             enrollment: 400,
             lec_sections: 2,
             lab_sections: 4,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert!(calculated_ta_fraction.lab_amount > 0.0);
+        assert!(calculated_ta_fraction.lab_amount > Fixed::ZERO);
         assert!(calculated_ta_fraction.total > calculated_ta_fraction.lab_amount);
     }
 
@@ -637,12 +1597,13 @@ mod tests {
             enrollment: 150,
             lec_sections: 1,
             lab_sections: 2,
-            unit_weight: 1.0,
+            unit_weight: Fixed::new(1.0),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert!(calculated_ta_fraction.total > 0.0);
+        assert!(calculated_ta_fraction.total > Fixed::ZERO);
     }
 
     #[test]
@@ -654,12 +1615,13 @@ mod tests {
             enrollment: 200,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert!(calculated_ta_fraction.total > 1.0);
+        assert!(calculated_ta_fraction.total > Fixed::new(1.0));
     }
 
     #[test]
@@ -671,14 +1633,15 @@ mod tests {
             enrollment: 100,
             lec_sections: 2,
             lab_sections: 4,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
         // Should be minimum of 2.5
-        assert!(calculated_ta_fraction.total >= 2.5);
+        assert!(calculated_ta_fraction.total >= Fixed::new(2.5));
     }
 
     #[test]
@@ -690,13 +1653,14 @@ mod tests {
             enrollment: 150,
             lec_sections: 1,
             lab_sections: 3,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 2.5);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(2.5));
     }
 
     // Additional comprehensive test cases for improved coverage
@@ -704,50 +1668,50 @@ mod tests {
     #[test]
     fn apply_rounding_with_6_hours() {
         // 78 hours / 130 = 0.6, round = 2, so 2/4 = 0.5
-        let result = apply_rounding(78.0);
-        assert_eq!(result, 0.5);
+        let result = round_ta_fraction(Fixed::new(78.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.5));
     }
 
     #[test]
     fn apply_rounding_with_7_hours() {
         // 91 hours / 130 ≈ 0.7, round = 3, so 3/4 = 0.75
-        let result = apply_rounding(91.0);
-        assert_eq!(result, 0.75);
+        let result = round_ta_fraction(Fixed::new(91.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.75));
     }
 
     #[test]
     fn apply_rounding_with_8_hours() {
         // 104 hours / 130 = 0.8, round = 3, so 3/4 = 0.75
-        let result = apply_rounding(104.0);
-        assert_eq!(result, 0.75);
+        let result = round_ta_fraction(Fixed::new(104.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.75));
     }
 
     #[test]
     fn apply_rounding_with_10_hours() {
         // 149 hours / 130 ≈ 1.15, round = 5, so 5/4 = 1.25
-        let result = apply_rounding(149.0);
-        assert!(result >= 1.0 && result <= 1.5);
+        let result = round_ta_fraction(Fixed::new(149.0), NEAREST_QUARTER);
+        assert!((Fixed::new(1.0)..=Fixed::new(1.5)).contains(&result));
     }
 
     #[test]
     fn apply_rounding_with_12_hours() {
         // 156 hours / 130 = 1.2, round = 5, so 5/4 = 1.25
-        let result = apply_rounding(156.0);
-        assert_eq!(result, 1.25);
+        let result = round_ta_fraction(Fixed::new(156.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(1.25));
     }
 
     #[test]
     fn apply_rounding_with_16_hours() {
         // 195 hours / 130 = 1.5, round = 6, so 6/4 = 1.5
-        let result = apply_rounding(195.0);
-        assert_eq!(result, 1.5);
+        let result = round_ta_fraction(Fixed::new(195.0), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(1.5));
     }
 
     #[test]
     fn apply_rounding_with_small_fraction() {
         // 6.5 hours / 130 = 0.05, round = 0, so 0/4 = 0.0
-        let result = apply_rounding(6.5);
-        assert_eq!(result, 0.0);
+        let result = round_ta_fraction(Fixed::new(6.5), NEAREST_QUARTER);
+        assert_eq!(result, Fixed::new(0.0));
     }
 
     #[test]
@@ -759,7 +1723,8 @@ mod tests {
             enrollment: 100,
             lec_sections: 0,
             lab_sections: 4,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
@@ -769,7 +1734,7 @@ mod tests {
             calculated_ta_fraction.lab_amount,
             calculated_ta_fraction.total
         );
-        assert!(calculated_ta_fraction.total > 0.0);
+        assert!(calculated_ta_fraction.total > Fixed::ZERO);
     }
 
     #[test]
@@ -781,14 +1746,15 @@ mod tests {
             enrollment: 200,
             lec_sections: 1,
             lab_sections: 4,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        // Should be at least 2.5 due to MIN_ALLOC rule
-        assert!(calculated_ta_fraction.total >= 2.5);
+        // FIXED at 2.5 however many students there are
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(2.5));
     }
 
     #[test]
@@ -800,14 +1766,15 @@ mod tests {
             enrollment: 50,
             lec_sections: 1,
             lab_sections: 1,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
         // Should be at least 2.5 due to MIN_ALLOC rule
-        assert_eq!(calculated_ta_fraction.total, 2.5);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(2.5));
     }
 
     #[test]
@@ -819,14 +1786,15 @@ mod tests {
             enrollment: 1000,
             lec_sections: 5,
             lab_sections: 20,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
         // Should be capped at 6.0 due to MAX_ALLOC rule
-        assert_eq!(calculated_ta_fraction.total, 6.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(6.0));
     }
 
     #[test]
@@ -838,13 +1806,14 @@ mod tests {
             enrollment: 500,
             lec_sections: 2,
             lab_sections: 0,
-            unit_weight: 1.0,
+            unit_weight: Fixed::new(1.0),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.0));
     }
 
     #[test]
@@ -856,20 +1825,21 @@ mod tests {
             enrollment: 200,
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
-        assert_eq!(calculated_ta_fraction.total, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.0));
     }
 
     #[test]
     fn determine_course_type_boundary_level_5() {
         let course_name = String::from("ECE 599");
 
-        let ct = determine_course_type(&course_name);
+        let ct = determine_course_type(&code(&course_name));
 
         assert_eq!(ct, UNDERGRAD);
     }
@@ -878,7 +1848,7 @@ mod tests {
     fn determine_course_type_boundary_level_6() {
         let course_name = String::from("ECE 600");
 
-        let ct = determine_course_type(&course_name);
+        let ct = determine_course_type(&code(&course_name));
 
         assert_eq!(ct, GRAD);
     }
@@ -892,13 +1862,14 @@ mod tests {
             enrollment: 500,
             lec_sections: 4,
             lab_sections: 8,
-            unit_weight: 0.75,
+            unit_weight: Fixed::new(0.75),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert!(calculated_ta_fraction.total > 0.0);
-        assert!(calculated_ta_fraction.lab_amount > 0.0);
+        assert!(calculated_ta_fraction.total > Fixed::ZERO);
+        assert!(calculated_ta_fraction.lab_amount > Fixed::ZERO);
         assert!(calculated_ta_fraction.total > calculated_ta_fraction.lab_amount);
     }
 
@@ -911,13 +1882,14 @@ mod tests {
             enrollment: 100,
             lec_sections: 2,
             lab_sections: 0,
-            unit_weight: 1.0,
+            unit_weight: Fixed::new(1.0),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
         // First year courses with unit weight >= 0.5 get extra adjustment
-        assert!(calculated_ta_fraction.total > 0.5);
+        assert!(calculated_ta_fraction.total > Fixed::new(0.5));
     }
 
     #[test]
@@ -929,21 +1901,22 @@ mod tests {
             enrollment: 9, // Below minimum
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.5,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
-        assert_eq!(calculated_ta_fraction.total, 0.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(0.0));
     }
 
     #[test]
     fn check_if_lab_only_with_various_formats() {
         // Test different naming formats - LAB_ONLY_COURSES has uppercase entries
-        assert_eq!(check_if_lab_only("ECE198"), true);
-        assert_eq!(check_if_lab_only("ECE 198"), true);
-        assert_eq!(check_if_lab_only("NE 340L"), true);
-        assert_eq!(check_if_lab_only("ECE298"), true);
+        assert!(check_if_lab_only(&code("ECE198")));
+        assert!(check_if_lab_only(&code("ECE 198")));
+        assert!(check_if_lab_only(&code("NE 340L")));
+        assert!(check_if_lab_only(&code("ECE298")));
     }
 
     #[test]
@@ -955,14 +1928,15 @@ mod tests {
             enrollment: 500,
             lec_sections: 3,
             lab_sections: 0,
-            unit_weight: 0.25,
+            unit_weight: Fixed::new(0.25),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
         let calculated_ta_fraction = check_for_special_case(&c, calculated_ta_fraction);
 
         // ECE190 uses PER_SECTION rule with 1.0 per section
-        assert_eq!(calculated_ta_fraction.total, 3.0);
+        assert_eq!(calculated_ta_fraction.total, Fixed::new(3.0));
     }
 
     #[test]
@@ -974,12 +1948,13 @@ mod tests {
             enrollment: 21, // Just above minimum
             lec_sections: 1,
             lab_sections: 0,
-            unit_weight: 0.25,
+            unit_weight: Fixed::new(0.25),
+            ..Default::default()
         };
 
         let calculated_ta_fraction = calculate_ta_hours(&c);
 
         // May result in 0 if below MIN_TA_THRESHOLD after rounding
-        assert!(calculated_ta_fraction.total >= 0.0);
+        assert!(calculated_ta_fraction.total >= Fixed::ZERO);
     }
 }
@@ -0,0 +1,390 @@
+use crate::calculator::{calculate_ta_hours, find_special_case, resolve_course_type};
+use crate::configuration::{
+    CALIBRATION_MIN_TERMS_FOR_OUTLIER, CALIBRATION_OUTLIER_THRESHOLD, FULL_TA_HOURS,
+    GRADUATE_COURSE, MENG_COURSE, PROFESSIONAL_DEV_COURSE, UNDERGRADUATE_COURSE,
+};
+use crate::fixed::Fixed;
+use crate::input::{find_column, parse_course_record, InputColumns};
+use crate::types::CourseType::{FIRST_YEAR, GRAD, MENG, PROFESSIONAL_DEV, UNDERGRAD};
+use crate::types::{CalculationRule, Course, CourseType, TAHourAllocation};
+
+// The historical file is the normal input format with two extra columns, Term and
+// Actual Allocation, which can go anywhere after the first six.
+pub struct HistoricalRecord {
+    pub course: Course,
+    pub term: String,
+    pub actual_allocation: Fixed,
+}
+
+struct Observation {
+    term: String,
+    course_name: String,
+    student_hours: Fixed,
+    term_hours: Fixed,
+    current_allocation: Fixed,
+    actual_allocation: Fixed,
+}
+
+// The fit itself is statistics rather than money, so it stays in floating point.
+#[derive(Debug, PartialEq)]
+pub struct CalibrationFit {
+    pub per_student_scale: f64,
+    pub per_term_scale: f64,
+}
+
+pub struct Residual {
+    pub term: String,
+    pub course_name: String,
+    pub actual: Fixed,
+    pub current: Fixed,
+    pub fitted: Fixed,
+    pub residual: Fixed,
+}
+
+pub fn calibrate(path: &String) {
+    let records = read_history_file(path);
+    println!(
+        "Calibrating configuration against {} historical course offerings...",
+        records.len()
+    );
+
+    let tables: [(&str, &[TAHourAllocation], &[CourseType]); 4] = [
+        (
+            "UNDERGRADUATE_COURSE",
+            UNDERGRADUATE_COURSE,
+            &[FIRST_YEAR, UNDERGRAD],
+        ),
+        ("GRADUATE_COURSE", GRADUATE_COURSE, &[GRAD]),
+        (
+            "PROFESSIONAL_DEV_COURSE",
+            PROFESSIONAL_DEV_COURSE,
+            &[PROFESSIONAL_DEV],
+        ),
+        ("MENG_COURSE", MENG_COURSE, &[MENG]),
+    ];
+
+    let mut residuals: Vec<Residual> = Vec::new();
+    for (table_name, table, course_types) in tables {
+        let observations = collect_observations(&records, course_types);
+        if observations.is_empty() {
+            println!("No usable history for {}; leaving it as is.", table_name);
+            continue;
+        }
+        let fit = fit_scales(&observations);
+        println!(
+            "Fitted {} from {} offerings (per-student scale {:.3}, per-term scale {:.3}):",
+            table_name,
+            observations.len(),
+            fit.per_student_scale,
+            fit.per_term_scale
+        );
+        for allocation in table {
+            let scale = if allocation.calc_rule == CalculationRule::PER_STUDENT {
+                fit.per_student_scale
+            } else {
+                fit.per_term_scale
+            };
+            println!(
+                "  {}: {:.2} -> {:.2} (Calculation Rule: {:?})",
+                allocation.name,
+                allocation.hours,
+                Fixed::new(allocation.hours.to_f64() * scale),
+                allocation.calc_rule
+            );
+        }
+        residuals.extend(compute_residuals(&observations, &fit));
+    }
+
+    println!("Residuals per course (actual minus fitted TA allocation):");
+    for r in &residuals {
+        println!(
+            "  {} {}: actual {:.2}, current {:.2}, fitted {:.2}, residual {:+.2}",
+            r.term, r.course_name, r.actual, r.current, r.fitted, r.residual
+        );
+    }
+
+    let outliers = find_persistent_outliers(&residuals);
+    if outliers.is_empty() {
+        println!("No persistent outliers found.");
+    }
+    for (course_name, mean_residual) in outliers {
+        println!(
+            "Persistent outlier: {} is off by more than {} TA in every term (mean residual {:+.2})",
+            course_name, CALIBRATION_OUTLIER_THRESHOLD, mean_residual
+        );
+    }
+}
+
+pub fn read_history_file(path: &String) -> Vec<HistoricalRecord> {
+    let mut records: Vec<HistoricalRecord> = Vec::new();
+    let mut rdr = csv::Reader::from_path(path).unwrap();
+    let headers = rdr.headers().unwrap().clone();
+    let columns = InputColumns::from_headers(&headers);
+    let term_column = find_column(&headers, "Term").expect("No Term column in history file");
+    let actual_column = find_column(&headers, "Actual Allocation")
+        .expect("No Actual Allocation column in history file");
+    for (row, result) in rdr.records().enumerate() {
+        let record = result.unwrap();
+        let course = parse_course_record(&record, &columns)
+            .unwrap_or_else(|e| panic!("{} (row {} of {})", e, row + 2, path));
+        records.push(HistoricalRecord {
+            course,
+            term: record.get(term_column).unwrap().trim().to_string(),
+            actual_allocation: record.get(actual_column).unwrap().trim().parse().unwrap(),
+        });
+    }
+    records
+}
+
+// Special cases and courses under the enrollment threshold don't come from the rule tables,
+// so they would only drag the fit around.
+fn collect_observations(
+    records: &[HistoricalRecord],
+    course_types: &[CourseType],
+) -> Vec<Observation> {
+    let mut observations: Vec<Observation> = Vec::new();
+    for r in records {
+        let code = r.course.code();
+        if !course_types.contains(&resolve_course_type(&r.course)) {
+            continue;
+        }
+        if find_special_case(&code).is_some() {
+            println!(
+                "Skipping {} {} because it is a special case.",
+                r.term, r.course.name
+            );
+            continue;
+        }
+        let allocation = calculate_ta_hours(&r.course);
+        if allocation.components.is_empty() {
+            continue;
+        }
+        let mut student_hours = Fixed::ZERO;
+        let mut term_hours = Fixed::ZERO;
+        for component in &allocation.components {
+            if component.calc_rule == CalculationRule::PER_STUDENT {
+                student_hours += component.hours;
+            } else {
+                term_hours += component.hours;
+            }
+        }
+        observations.push(Observation {
+            term: r.term.clone(),
+            course_name: code.to_string(),
+            student_hours,
+            term_hours,
+            current_allocation: allocation.total,
+            actual_allocation: r.actual_allocation,
+        });
+    }
+    observations
+}
+
+// Least squares fit of actual hours = a * (per-student hours) + b * (per-term hours).
+// Everything that isn't PER_STUDENT (per term, per section, per lab) counts as per-term.
+// If the two can't be told apart (e.g. every course has the same enrollment), fall back
+// to scaling everything by the same amount.
+fn fit_scales(observations: &[Observation]) -> CalibrationFit {
+    let mut ss: f64 = 0.0;
+    let mut st: f64 = 0.0;
+    let mut tt: f64 = 0.0;
+    let mut sy: f64 = 0.0;
+    let mut ty: f64 = 0.0;
+    for o in observations {
+        let s = o.student_hours.to_f64();
+        let t = o.term_hours.to_f64();
+        let y = (o.actual_allocation * FULL_TA_HOURS).to_f64();
+        ss += s * s;
+        st += s * t;
+        tt += t * t;
+        sy += s * y;
+        ty += t * y;
+    }
+
+    let det = ss * tt - st * st;
+    if det.abs() > 1e-6 * ss.max(tt).max(1.0).powi(2) {
+        let a = (sy * tt - ty * st) / det;
+        let b = (ss * ty - st * sy) / det;
+        // A negative hourly rate makes no sense, so pin it at zero and refit the other one
+        if a < 0.0 {
+            return CalibrationFit {
+                per_student_scale: 0.0,
+                per_term_scale: ty / tt,
+            };
+        }
+        if b < 0.0 {
+            return CalibrationFit {
+                per_student_scale: sy / ss,
+                per_term_scale: 0.0,
+            };
+        }
+        return CalibrationFit {
+            per_student_scale: a,
+            per_term_scale: b,
+        };
+    }
+
+    let total_squared = ss + 2.0 * st + tt;
+    let scale = if total_squared == 0.0 {
+        1.0
+    } else {
+        (sy + ty) / total_squared
+    };
+    CalibrationFit {
+        per_student_scale: scale,
+        per_term_scale: scale,
+    }
+}
+
+fn compute_residuals(observations: &[Observation], fit: &CalibrationFit) -> Vec<Residual> {
+    observations
+        .iter()
+        .map(|o| {
+            let fitted_hours = o.student_hours.to_f64() * fit.per_student_scale
+                + o.term_hours.to_f64() * fit.per_term_scale;
+            let fitted = Fixed::new(fitted_hours) / FULL_TA_HOURS;
+            Residual {
+                term: o.term.clone(),
+                course_name: o.course_name.clone(),
+                actual: o.actual_allocation,
+                current: o.current_allocation,
+                fitted,
+                residual: o.actual_allocation - fitted,
+            }
+        })
+        .collect()
+}
+
+// A course is a persistent outlier if it shows up in enough terms and the fit misses it
+// by more than the threshold every single time.
+pub fn find_persistent_outliers(residuals: &[Residual]) -> Vec<(String, Fixed)> {
+    let mut course_names: Vec<&String> = residuals.iter().map(|r| &r.course_name).collect();
+    course_names.sort();
+    course_names.dedup();
+
+    let mut outliers: Vec<(String, Fixed)> = Vec::new();
+    for course_name in course_names {
+        let course_residuals: Vec<Fixed> = residuals
+            .iter()
+            .filter(|r| &r.course_name == course_name)
+            .map(|r| r.residual)
+            .collect();
+        if course_residuals.len() < CALIBRATION_MIN_TERMS_FOR_OUTLIER {
+            continue;
+        }
+        if course_residuals
+            .iter()
+            .all(|r| r.abs() > CALIBRATION_OUTLIER_THRESHOLD)
+        {
+            let count = Fixed::from_int(course_residuals.len() as i32);
+            let mean = course_residuals.iter().copied().sum::<Fixed>() / count;
+            outliers.push((course_name.clone(), mean));
+        }
+    }
+    outliers
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::calibration::{
+        compute_residuals, find_persistent_outliers, fit_scales, read_history_file, Observation,
+        Residual,
+    };
+    use crate::fixed::Fixed;
+
+    fn observation(student_hours: f64, term_hours: f64, actual_allocation: f64) -> Observation {
+        Observation {
+            term: "W25".to_string(),
+            course_name: "ECE252".to_string(),
+            student_hours: Fixed::new(student_hours),
+            term_hours: Fixed::new(term_hours),
+            current_allocation: Fixed::ZERO,
+            actual_allocation: Fixed::new(actual_allocation),
+        }
+    }
+
+    fn residual(term: &str, course_name: &str, residual: f64) -> Residual {
+        Residual {
+            term: term.to_string(),
+            course_name: course_name.to_string(),
+            actual: Fixed::ZERO,
+            current: Fixed::ZERO,
+            fitted: Fixed::ZERO,
+            residual: Fixed::new(residual),
+        }
+    }
+
+    #[test]
+    fn parse_example_history_file() {
+        let records = read_history_file(&String::from("test_files/history.csv"));
+
+        assert_eq!(records.len(), 6);
+        assert_eq!(records.first().unwrap().course.name, "ECE 252");
+        assert_eq!(records.first().unwrap().term, "F24");
+        assert_eq!(records.first().unwrap().actual_allocation, Fixed::new(3.0));
+    }
+
+    #[test]
+    fn fit_scales_recovers_known_scales() {
+        // Actual hours are 2 * student hours + 0.5 * term hours
+        let observations = vec![
+            observation(65.0, 130.0, 1.5),
+            observation(130.0, 65.0, 2.25),
+            observation(32.5, 260.0, 1.5),
+        ];
+
+        let fit = fit_scales(&observations);
+
+        assert!((fit.per_student_scale - 2.0).abs() < 0.001);
+        assert!((fit.per_term_scale - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn fit_scales_falls_back_to_single_scale_when_degenerate() {
+        let observations = vec![observation(65.0, 65.0, 2.0), observation(65.0, 65.0, 2.0)];
+
+        let fit = fit_scales(&observations);
+
+        assert!((fit.per_student_scale - 2.0).abs() < 0.001);
+        assert_eq!(fit.per_student_scale, fit.per_term_scale);
+    }
+
+    #[test]
+    fn fit_scales_never_produces_negative_rates() {
+        // The best unconstrained fit here wants a negative per-term rate
+        let observations = vec![observation(65.0, 130.0, 0.5), observation(130.0, 65.0, 2.0)];
+
+        let fit = fit_scales(&observations);
+
+        assert_eq!(fit.per_term_scale, 0.0);
+        assert!(fit.per_student_scale > 0.0);
+    }
+
+    #[test]
+    fn residuals_are_actual_minus_fitted() {
+        let observations = vec![observation(65.0, 65.0, 1.5)];
+        let fit = fit_scales(&[observation(65.0, 65.0, 1.0)]);
+
+        let residuals = compute_residuals(&observations, &fit);
+
+        assert_eq!(residuals.first().unwrap().fitted, Fixed::new(1.0));
+        assert_eq!(residuals.first().unwrap().residual, Fixed::new(0.5));
+    }
+
+    #[test]
+    fn persistent_outlier_needs_to_miss_every_term() {
+        let residuals = vec![
+            residual("F24", "ECE252", 1.0),
+            residual("W25", "ECE252", 0.75),
+            residual("F24", "ECE222", 1.0),
+            residual("W25", "ECE222", 0.25),
+            residual("W25", "ECE606", -2.0),
+        ];
+
+        let outliers = find_persistent_outliers(&residuals);
+
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers.first().unwrap().0, "ECE252");
+        assert_eq!(outliers.first().unwrap().1, Fixed::new(0.875));
+    }
+}
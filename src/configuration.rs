@@ -1,128 +1,379 @@
-use crate::types::AllocationType::{LAB, LECTURE, NON_LAB};
-use crate::types::{CalculationRule, TAHourAllocation};
+use crate::fixed::Fixed;
+use crate::types::AllocationType::{LAB, LECTURE};
+use crate::types::Applicability::{COURSE_TYPES, NOT_LAB_ONLY, WITHOUT_LABS, WITH_LABS};
+use crate::types::Assessment::{ASSIGNMENT, FINAL, MIDTERM};
+use crate::types::CourseType::FIRST_YEAR;
+use crate::types::DeliveryMode::{HYBRID, ONLINE};
+use crate::types::{
+    AssessmentProfile, CalculationRule, DeliveryModeAdjustment, DuplicatePolicy, RoundingMode,
+    RoundingPolicy, RoundingScope, SupportUplift, TAHourAllocation, UpliftFormula,
+};
 
-pub const FULL_TA_HOURS: f32 = 130.0;
-pub const MIN_TA_THRESHOLD: f32 = 0.25;
-pub const LAB_RATIO_DENOMINATOR: f32 = 15.0;
-pub const FIRST_YEAR_EXTRA_TA_HOURS: f32 = 0.0;
+pub const FULL_TA_HOURS: Fixed = Fixed::new(130.0);
+pub const MIN_TA_THRESHOLD: Fixed = Fixed::new(0.25);
 
-pub const LAB_INSTRUCTOR_ADJUSTMENT: f32 = 1.0;
+// TOTAL rounds the overall allocation and the lab share (the lecture share is what's left);
+// PER_COMPONENT rounds the lab and lecture shares separately and adds them up.
+pub const ROUNDING_POLICY: RoundingPolicy = RoundingPolicy {
+    mode: RoundingMode::NEAREST_QUARTER,
+    scope: RoundingScope::TOTAL,
+};
+// What to do with more than one input row for the same course code, unless the command line
+// says otherwise with --duplicates=error|merge|separate.
+pub const DUPLICATE_POLICY: DuplicatePolicy = DuplicatePolicy::SEPARATE;
+pub const LAB_RATIO_DENOMINATOR: Fixed = Fixed::new(15.0);
 
-pub const MIN_UNIT_WEIGHT_FOR_1YE_ADJUSTMENT: f32 = 0.5;
+pub const LAB_INSTRUCTOR_ADJUSTMENT: Fixed = Fixed::new(1.0);
+
+// First-year courses get extra hours due to higher needs for support. This shows up as
+// its own line in the breakdown, counted toward the lab or lecture fraction by alloc_type.
+pub const FIRST_YEAR_UPLIFT: SupportUplift = SupportUplift {
+    name: "First Year Support",
+    formula: UpliftFormula::PER_STUDENT(Fixed::new(0.0)),
+    alloc_type: LECTURE,
+    applies_to: COURSE_TYPES(&[FIRST_YEAR]),
+    min_unit_weight: Fixed::new(0.5),
+};
+
+// Rules with scale_by_unit_weight set are written for a course of this unit weight,
+// so a 0.25 unit course gets half of them and a 1.0 unit course twice as much.
+pub const UNIT_WEIGHT_BASELINE: Fixed = Fixed::new(0.5);
 
 pub const MIN_ENROLLMENT_FOR_TA_ALLOC_UG: i32 = 20;
 pub const MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD: i32 = 15;
+pub const MIN_ENROLLMENT_FOR_TA_ALLOC_PD: i32 = 10;
+pub const MIN_ENROLLMENT_FOR_TA_ALLOC_MENG: i32 = 20;
+
+// The assessments the marking rates below are written for. A course with two midterms
+// gets twice the Midterm Marking; one without a final gets no Final Marking.
+pub const STANDARD_ASSESSMENTS: AssessmentProfile = AssessmentProfile {
+    midterms: 1,
+    final_exam: true,
+    assignments: 4,
+};
+
+// Rules that change for online and hybrid courses, matched by rule name in whichever
+// table the course uses. In-person courses use the tables as they are.
+pub const DELIVERY_MODE_ADJUSTMENTS: &[DeliveryModeAdjustment] = &[
+    DeliveryModeAdjustment {
+        mode: ONLINE,
+        rule: "Exam Proctoring",
+        factor: Fixed::new(0.0),
+        reason: "Exams are written online",
+    },
+    DeliveryModeAdjustment {
+        mode: ONLINE,
+        rule: "Office Hours Online",
+        factor: Fixed::new(2.0),
+        reason: "All office hours are online",
+    },
+    DeliveryModeAdjustment {
+        mode: HYBRID,
+        rule: "Office Hours Online",
+        factor: Fixed::new(1.5),
+        reason: "Some office hours are online",
+    },
+];
+
+// Plausibility limits for the validate step. Courses outside them still get calculated,
+// but the run warns about them first.
+pub const MIN_UNIT_WEIGHT: Fixed = Fixed::new(0.25);
+pub const MAX_UNIT_WEIGHT: Fixed = Fixed::new(1.5);
+pub const MAX_STUDENTS_PER_LEC_SECTION: i32 = 400;
+pub const MIN_STUDENTS_PER_LAB_SECTION: i32 = 5;
+
+// The letters command writes one file per instructor into this directory, unless another
+// one is given on the command line.
+pub const LETTERS_DIRECTORY: &str = "letters";
+
+// {instructor} is the instructor's name and {courses} is one LETTER_COURSE_TEMPLATE per course.
+pub const LETTER_TEMPLATE: &str = "Dear {instructor},
+
+Here is the TA support planned for your course(s) next term, based on the enrollment
+and section numbers we have right now. The hours come from the department's standard
+rules; anything unusual about a course is noted below.
+
+{courses}
+If any of the numbers the calculation started from look wrong (enrollment, sections,
+labs), please let me know before the allocations are finalized.
+";
+
+// {course}, {enrollment}, {allocation}, {lecture}, {lab}, {co_instructors}, {special_case},
+// {full_ta_hours} and {breakdown} are filled in for each course.
+pub const LETTER_COURSE_TEMPLATE: &str = "## {course}
+
+* Enrollment: {enrollment}
+* TA allocation: {allocation} (lecture {lecture}, lab {lab})
+{co_instructors}{special_case}
+How it was worked out ({full_ta_hours} hours is one full TA):
+
+{breakdown}
+";
+
+pub const CALIBRATION_OUTLIER_THRESHOLD: Fixed = Fixed::new(0.5);
+pub const CALIBRATION_MIN_TERMS_FOR_OUTLIER: usize = 2;
 
+// By default lab rules apply to courses with lab sections, assignment marking only to
+// courses without labs, and everything else to any course that isn't lab-only.
 pub const UNDERGRADUATE_COURSE: &[TAHourAllocation] = &[
     TAHourAllocation {
         name: "Midterm Marking",
-        hours: 0.2,
+        hours: Fixed::new(0.2),
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(MIDTERM),
     },
     TAHourAllocation {
         name: "Final Marking",
-        hours: 0.33,
+        hours: Fixed::new(0.33),
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(FINAL),
     },
     TAHourAllocation {
         name: "Tutorials",
-        hours: 11.0,
-        calc_rule: CalculationRule::PER_LEC_SECTION,
+        hours: Fixed::new(11.0),
+        calc_rule: CalculationRule::PER_TUT_SECTION,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Tutorial Prep",
-        hours: 11.0,
+        hours: Fixed::new(11.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Office Hours",
-        hours: 11.0,
+        hours: Fixed::new(11.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Office Hours Online",
-        hours: 0.17,
+        hours: Fixed::new(0.17),
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Lab Delivery",
-        hours: 15.0,
+        hours: Fixed::new(15.0),
         calc_rule: CalculationRule::PER_LAB,
         alloc_type: LAB,
+        applies_to: WITH_LABS,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Lab Prep",
-        hours: 5.0, // 1/3 * 5 * 3 * # Labs
+        hours: Fixed::new(5.0), // 1/3 * 5 * 3 * # Labs
         calc_rule: CalculationRule::PER_LAB,
         alloc_type: LAB,
+        applies_to: WITH_LABS,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Lab Marking",
-        hours: 0.0, // Previously: # (Students / 2) * 13 * 5
+        hours: Fixed::new(0.0), // Previously: # (Students / 2) * 13 * 5
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LAB,
+        applies_to: WITH_LABS,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Assignment Marking",
-        hours: 1.0,
+        hours: Fixed::new(1.0),
         calc_rule: CalculationRule::PER_STUDENT,
-        alloc_type: NON_LAB,
+        alloc_type: LECTURE,
+        applies_to: WITHOUT_LABS,
+        scale_by_unit_weight: false,
+        assessment: Some(ASSIGNMENT),
     },
     TAHourAllocation {
         name: "Exam Proctoring",
-        hours: 0.17,
+        hours: Fixed::new(0.17),
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Extra TA Hours",
-        hours: 0.0,
+        hours: Fixed::new(0.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
 ];
 
 pub const GRADUATE_COURSE: &[TAHourAllocation] = &[
     TAHourAllocation {
         name: "Final Marking",
-        hours: 0.53,
+        hours: Fixed::new(0.53),
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(FINAL),
     },
     TAHourAllocation {
         name: "Tutorials",
-        hours: 12.0,
+        hours: Fixed::new(12.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Office Hours",
-        hours: 12.0,
+        hours: Fixed::new(12.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Assignment Marking",
-        hours: 1.0,
+        hours: Fixed::new(1.0),
         calc_rule: CalculationRule::PER_STUDENT,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(ASSIGNMENT),
     },
     TAHourAllocation {
         name: "Exam Proctoring",
-        hours: 3.0,
+        hours: Fixed::new(3.0),
+        calc_rule: CalculationRule::PER_TERM,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
+    },
+    TAHourAllocation {
+        name: "Extra TA Hours",
+        hours: Fixed::new(0.0),
+        calc_rule: CalculationRule::PER_TERM,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
+    },
+];
+
+// PD courses are delivered online, so there are no tutorials or exams to staff,
+// but there is a lot of marking and online discussion.
+// These rates (and MIN_ENROLLMENT_FOR_TA_ALLOC_PD) aren't from a published policy yet,
+// which is why ECE6607PD and ECE6608PD still have their FIXED special cases.
+pub const PROFESSIONAL_DEV_COURSE: &[TAHourAllocation] = &[
+    TAHourAllocation {
+        name: "Assignment Marking",
+        hours: Fixed::new(2.0),
+        calc_rule: CalculationRule::PER_STUDENT,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(ASSIGNMENT),
+    },
+    TAHourAllocation {
+        name: "Office Hours Online",
+        hours: Fixed::new(24.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
+    },
+    TAHourAllocation {
+        name: "Discussion Board Moderation",
+        hours: Fixed::new(0.5),
+        calc_rule: CalculationRule::PER_STUDENT,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
+    },
+    TAHourAllocation {
+        name: "Extra TA Hours",
+        hours: Fixed::new(0.0),
+        calc_rule: CalculationRule::PER_TERM,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
+    },
+];
+
+// Course-based MEng offerings are much bigger than research-stream grad courses,
+// so more of the work scales with enrollment.
+// Like the PD table, these rates and MIN_ENROLLMENT_FOR_TA_ALLOC_MENG are a starting
+// point, not a published policy; nothing is MENG unless the Type column says so.
+pub const MENG_COURSE: &[TAHourAllocation] = &[
+    TAHourAllocation {
+        name: "Final Marking",
+        hours: Fixed::new(0.5),
+        calc_rule: CalculationRule::PER_STUDENT,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(FINAL),
+    },
+    TAHourAllocation {
+        name: "Assignment Marking",
+        hours: Fixed::new(1.25),
+        calc_rule: CalculationRule::PER_STUDENT,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: Some(ASSIGNMENT),
+    },
+    TAHourAllocation {
+        name: "Office Hours",
+        hours: Fixed::new(12.0),
+        calc_rule: CalculationRule::PER_TERM,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
+    },
+    TAHourAllocation {
+        name: "Exam Proctoring",
+        hours: Fixed::new(0.1),
+        calc_rule: CalculationRule::PER_STUDENT,
+        alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
     TAHourAllocation {
         name: "Extra TA Hours",
-        hours: 0.0,
+        hours: Fixed::new(0.0),
         calc_rule: CalculationRule::PER_TERM,
         alloc_type: LECTURE,
+        applies_to: NOT_LAB_ONLY,
+        scale_by_unit_weight: false,
+        assessment: None,
     },
 ];
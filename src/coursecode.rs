@@ -0,0 +1,148 @@
+use std::fmt;
+
+/// A parsed course code such as `ECE 405C`: subject `ECE`, catalog number `405`, suffix `C`.
+/// Spacing, hyphens and case are normalized away, so `ECE 150`, `ece-150` and `ECE150`
+/// are all the same course.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CourseCode {
+    pub subject: String,
+    pub number: String,
+    pub suffix: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CourseCodeError {
+    pub code: String,
+    pub reason: &'static str,
+}
+
+impl CourseCode {
+    pub fn parse(code: &str) -> Result<CourseCode, CourseCodeError> {
+        let error = |reason| CourseCodeError {
+            code: code.to_string(),
+            reason,
+        };
+        let normalized: String = code
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if normalized.is_empty() {
+            return Err(error("the course code is empty"));
+        }
+        if !normalized.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(error(
+                "only letters, digits, spaces and hyphens are allowed",
+            ));
+        }
+
+        let subject: String = normalized
+            .chars()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect();
+        let rest = &normalized[subject.len()..];
+        let number: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let suffix = &rest[number.len()..];
+
+        if subject.is_empty() {
+            return Err(error("it doesn't start with a subject like ECE"));
+        }
+        if number.is_empty() {
+            return Err(error("there is no catalog number after the subject"));
+        }
+        if !suffix.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(error(
+                "the catalog number must be followed by letters only (e.g. L, A, PD)",
+            ));
+        }
+
+        Ok(CourseCode {
+            subject,
+            number,
+            suffix: suffix.to_string(),
+        })
+    }
+
+    /// The first digit of the catalog number: 1 for first year, 6+ for graduate courses.
+    pub fn level(&self) -> u32 {
+        self.number.chars().next().unwrap().to_digit(10).unwrap()
+    }
+}
+
+impl fmt::Display for CourseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.subject, self.number, self.suffix)
+    }
+}
+
+impl fmt::Display for CourseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid course code '{}': {}", self.code, self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coursecode::CourseCode;
+
+    #[test]
+    fn parse_plain_code() {
+        let code = CourseCode::parse("ECE150").unwrap();
+
+        assert_eq!(code.subject, "ECE");
+        assert_eq!(code.number, "150");
+        assert_eq!(code.suffix, "");
+        assert_eq!(code.level(), 1);
+    }
+
+    #[test]
+    fn parse_normalizes_spacing_hyphens_and_case() {
+        let expected = CourseCode::parse("NE650").unwrap();
+
+        assert_eq!(CourseCode::parse("NE-650").unwrap(), expected);
+        assert_eq!(CourseCode::parse(" ne 650 ").unwrap(), expected);
+        assert_eq!(CourseCode::parse("NE - 650").unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_suffixes() {
+        let lab = CourseCode::parse("NE340L").unwrap();
+        let pd = CourseCode::parse("ECE6607PD").unwrap();
+        let c = CourseCode::parse("ECE 405C").unwrap();
+
+        assert_eq!(lab.suffix, "L");
+        assert_eq!(pd.number, "6607");
+        assert_eq!(pd.suffix, "PD");
+        assert_eq!(pd.level(), 6);
+        assert_eq!(c.suffix, "C");
+        assert_eq!(c.to_string(), "ECE405C");
+    }
+
+    #[test]
+    fn parse_rejects_malformed_codes() {
+        assert_eq!(
+            CourseCode::parse("").unwrap_err().reason,
+            "the course code is empty"
+        );
+        assert_eq!(
+            CourseCode::parse("Capstone").unwrap_err().reason,
+            "there is no catalog number after the subject"
+        );
+        assert_eq!(
+            CourseCode::parse("150").unwrap_err().reason,
+            "it doesn't start with a subject like ECE"
+        );
+        assert!(CourseCode::parse("ECE150/250").is_err());
+        assert!(CourseCode::parse("ECE150L2").is_err());
+    }
+
+    #[test]
+    fn error_message_names_the_code() {
+        let e = CourseCode::parse("Capstone").unwrap_err();
+
+        assert_eq!(
+            e.to_string(),
+            "Invalid course code 'Capstone': there is no catalog number after the subject"
+        );
+    }
+}
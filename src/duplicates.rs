@@ -0,0 +1,194 @@
+use crate::coursecode::CourseCode;
+use crate::types::{Course, DuplicatePolicy};
+
+// Picks out --duplicates=error|merge|separate from the command line, if it's there,
+// and returns the other arguments.
+pub fn duplicate_policy_from_args(
+    args: Vec<String>,
+    default: DuplicatePolicy,
+) -> (Vec<String>, DuplicatePolicy) {
+    let mut policy = default;
+    let mut rest: Vec<String> = Vec::new();
+    for arg in args {
+        match arg.strip_prefix("--duplicates=") {
+            Some(value) => policy = value.parse().unwrap_or_else(|e: String| panic!("{}", e)),
+            None => rest.push(arg),
+        }
+    }
+    (rest, policy)
+}
+
+// Rows with the same normalized course code (ECE 150 and ECE150, say) are usually the same
+// course split between instructors, and calculating them separately double-counts.
+pub fn resolve_duplicates(
+    courses: Vec<Course>,
+    policy: DuplicatePolicy,
+) -> Result<Vec<Course>, String> {
+    let codes: Vec<CourseCode> = courses.iter().map(Course::code).collect();
+    let rows_with = |code: &CourseCode| codes.iter().filter(|c| *c == code).count();
+    let duplicated: Vec<String> = codes
+        .iter()
+        .enumerate()
+        .filter(|(i, code)| rows_with(code) > 1 && !codes[..*i].contains(code))
+        .map(|(_, code)| format!("{} ({} rows)", code, rows_with(code)))
+        .collect();
+    if duplicated.is_empty() {
+        return Ok(courses);
+    }
+
+    match policy {
+        DuplicatePolicy::ERROR => Err(format!(
+            "Duplicate courses in the input: {}. Fix the input or run with --duplicates=merge or --duplicates=separate.",
+            duplicated.join(", ")
+        )),
+        // Each merged row goes where its course first appears.
+        DuplicatePolicy::MERGE => {
+            println!("Merging duplicate rows for {}", duplicated.join(", "));
+            let mut groups: Vec<(&CourseCode, Vec<Course>)> = Vec::new();
+            for (c, code) in courses.into_iter().zip(&codes) {
+                match groups.iter_mut().find(|(g, _)| *g == code) {
+                    Some((_, rows)) => rows.push(c),
+                    None => groups.push((code, vec![c])),
+                }
+            }
+            Ok(groups.into_iter().map(|(_, rows)| merge_rows(rows)).collect())
+        }
+        // The rows stay in the order they were in.
+        DuplicatePolicy::SEPARATE => {
+            println!(
+                "Keeping duplicate rows for {} separate, marked as multi-instructor",
+                duplicated.join(", ")
+            );
+            Ok(courses
+                .into_iter()
+                .zip(&codes)
+                .map(|(c, code)| Course {
+                    multi_instructor: rows_with(code) > 1,
+                    ..c
+                })
+                .collect())
+        }
+    }
+}
+
+// Enrollment and sections are added up; everything else comes from the first row.
+fn merge_rows(rows: Vec<Course>) -> Course {
+    let mut rows = rows.into_iter();
+    let mut merged = rows.next().unwrap();
+    for c in rows {
+        if c.unit_weight != merged.unit_weight {
+            println!(
+                "Warning: {} has unit weight {} on one row and {} on another; using {}",
+                merged.name, merged.unit_weight, c.unit_weight, merged.unit_weight
+            );
+        }
+        if !c.instructor.is_empty() && !merged.instructor.split("; ").any(|i| i == c.instructor) {
+            if !merged.instructor.is_empty() {
+                merged.instructor.push_str("; ");
+            }
+            merged.instructor.push_str(&c.instructor);
+        }
+        merged.tut_sections = match (merged.tut_sections, c.tut_sections) {
+            (None, None) => None,
+            _ => Some(merged.tutorial_sections() + c.tutorial_sections()),
+        };
+        merged.enrollment += c.enrollment;
+        merged.lec_sections += c.lec_sections;
+        merged.lab_sections += c.lab_sections;
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duplicates::{duplicate_policy_from_args, resolve_duplicates};
+    use crate::fixed::Fixed;
+    use crate::types::Course;
+    use crate::types::DuplicatePolicy::{ERROR, MERGE, SEPARATE};
+
+    fn rows() -> Vec<Course> {
+        let row = |name: &str, instructor: &str, enrollment| Course {
+            name: String::from(name),
+            instructor: String::from(instructor),
+            enrollment,
+            lec_sections: 1,
+            lab_sections: 2,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        };
+        vec![
+            row("ECE 150", "First Instructor", 230),
+            row("ECE 250", "Third Instructor", 120),
+            row("ECE150", "Second Instructor", 220),
+        ]
+    }
+
+    #[test]
+    fn no_duplicates_is_left_alone() {
+        let courses = resolve_duplicates(rows().split_off(1), ERROR).unwrap();
+
+        assert_eq!(courses.len(), 2);
+        assert!(!courses.iter().any(|c| c.multi_instructor));
+    }
+
+    #[test]
+    fn error_policy_names_the_duplicates() {
+        let e = resolve_duplicates(rows(), ERROR).unwrap_err();
+
+        assert!(e.starts_with("Duplicate courses in the input: ECE150 (2 rows)."));
+    }
+
+    #[test]
+    fn merge_policy_adds_up_enrollment_and_sections() {
+        let courses = resolve_duplicates(rows(), MERGE).unwrap();
+        let ece150 = courses.first().unwrap();
+
+        assert_eq!(courses.len(), 2);
+        assert_eq!(ece150.name, "ECE 150");
+        assert_eq!(courses.get(1).unwrap().name, "ECE 250");
+        assert_eq!(ece150.instructor, "First Instructor; Second Instructor");
+        assert_eq!(ece150.enrollment, 450);
+        assert_eq!(ece150.lec_sections, 2);
+        assert_eq!(ece150.lab_sections, 4);
+        assert_eq!(ece150.tut_sections, None);
+    }
+
+    #[test]
+    fn separate_policy_marks_the_rows() {
+        let courses = resolve_duplicates(rows(), SEPARATE).unwrap();
+
+        assert_eq!(courses.len(), 3);
+        // The rows stay in input order
+        assert_eq!(courses.get(1).unwrap().name, "ECE 250");
+        assert!(courses.first().unwrap().multi_instructor);
+        assert!(!courses.get(1).unwrap().multi_instructor);
+        assert!(courses.get(2).unwrap().multi_instructor);
+    }
+
+    #[test]
+    fn merged_row_goes_where_the_course_first_appears() {
+        let mut courses = rows();
+        courses.rotate_left(1);
+
+        let courses = resolve_duplicates(courses, MERGE).unwrap();
+
+        assert_eq!(courses.len(), 2);
+        assert_eq!(courses.first().unwrap().name, "ECE 250");
+        assert_eq!(courses.get(1).unwrap().name, "ECE150");
+        assert_eq!(courses.get(1).unwrap().enrollment, 450);
+    }
+
+    #[test]
+    fn policy_flag_is_taken_out_of_the_arguments() {
+        let args = vec![
+            String::from("ta-calculations"),
+            String::from("--duplicates=merge"),
+            String::from("input.csv"),
+        ];
+
+        let (rest, policy) = duplicate_policy_from_args(args, SEPARATE);
+
+        assert_eq!(rest, vec!["ta-calculations", "input.csv"]);
+        assert_eq!(policy, MERGE);
+    }
+}
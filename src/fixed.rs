@@ -0,0 +1,323 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+// Four decimal places is plenty for hours (0.17 per student), unit weights (0.25)
+// and TA fractions (quarters), and every one of those is stored exactly.
+const DECIMAL_PLACES: u32 = 4;
+const SCALE: i64 = 10_i64.pow(DECIMAL_PLACES);
+
+/// A fixed-point decimal number, used for hours, unit weights and TA allocations so that
+/// adding up a couple dozen line items never drifts across a rounding boundary.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Fixed(i64);
+
+#[derive(Debug, PartialEq)]
+pub struct ParseFixedError(String);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+
+    /// Converts a literal to the nearest representable value. This is meant for the
+    /// constants in the configuration, where writing `Fixed::new(0.17)` reads naturally.
+    pub const fn new(value: f64) -> Fixed {
+        let scaled = value * SCALE as f64;
+        if scaled >= 0.0 {
+            Fixed((scaled + 0.5) as i64)
+        } else {
+            Fixed((scaled - 0.5) as i64)
+        }
+    }
+
+    pub const fn from_int(value: i32) -> Fixed {
+        Fixed(value as i64 * SCALE)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// Rounds to the nearest multiple of `step`, with halfway cases going away from zero
+    /// (the same as `f32::round`).
+    pub fn round_to(self, step: Fixed) -> Fixed {
+        Fixed(div_round(self.0 as i128, step.0 as i128) as i64 * step.0)
+    }
+
+    /// Rounds up to the next multiple of `step` (for positive steps).
+    pub fn ceil_to(self, step: Fixed) -> Fixed {
+        let quotient = self.0 / step.0;
+        if self.0 % step.0 > 0 {
+            Fixed((quotient + 1) * step.0)
+        } else {
+            Fixed(quotient * step.0)
+        }
+    }
+
+    pub fn abs(self) -> Fixed {
+        Fixed(self.0.abs())
+    }
+}
+
+// Integer division rounding halfway cases away from zero.
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if 2 * remainder.abs() >= denominator.abs() {
+        if (numerator < 0) == (denominator < 0) {
+            quotient + 1
+        } else {
+            quotient - 1
+        }
+    } else {
+        quotient
+    }
+}
+
+// Scales a raw value up by another factor of SCALE ahead of a division, so the quotient
+// keeps all four decimal places.
+fn widen(raw: i64) -> i128 {
+    raw as i128 * SCALE as i128
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed(div_round(self.0 as i128 * rhs.0 as i128, SCALE as i128) as i64)
+    }
+}
+
+impl Mul<i32> for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: i32) -> Fixed {
+        Fixed(self.0 * rhs as i64)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, rhs: Fixed) -> Fixed {
+        Fixed(div_round(widen(self.0), rhs.0 as i128) as i64)
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Fixed>>(iter: I) -> Fixed {
+        iter.fold(Fixed::ZERO, |a, b| a + b)
+    }
+}
+
+impl FromStr for Fixed {
+    type Err = ParseFixedError;
+
+    fn from_str(s: &str) -> Result<Fixed, ParseFixedError> {
+        let error = || ParseFixedError(format!("'{}' is not a decimal number", s));
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(error());
+        }
+        if !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(error());
+        }
+        if fraction.len() > DECIMAL_PLACES as usize {
+            return Err(ParseFixedError(format!(
+                "'{}' has more than {} decimal places",
+                s, DECIMAL_PLACES
+            )));
+        }
+
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| error())?
+        };
+        let padded = format!("{:0<width$}", fraction, width = DECIMAL_PLACES as usize);
+        let fraction: i64 = padded.parse().map_err(|_| error())?;
+        let value = whole
+            .checked_mul(SCALE)
+            .and_then(|w| w.checked_add(fraction))
+            .ok_or_else(error)?;
+        Ok(Fixed(if negative { -value } else { value }))
+    }
+}
+
+impl fmt::Display for ParseFixedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Without a precision this prints the shortest exact form ("4.5", "3"), like f32 did;
+// with one ("{:.2}") it rounds to that many places.
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = match f.precision() {
+            Some(places) if places < DECIMAL_PLACES as usize => {
+                let step = 10_i64.pow(DECIMAL_PLACES - places as u32);
+                let rounded = Fixed(self.0).round_to(Fixed(step)).0.abs();
+                let fraction = format!("{:04}", rounded % SCALE);
+                format_digits(rounded / SCALE, &fraction[..places])
+            }
+            Some(places) => {
+                let fraction = format!("{:04}", self.0.abs() % SCALE);
+                let padded = format!("{:0<width$}", fraction, width = places);
+                format_digits(self.0.abs() / SCALE, &padded)
+            }
+            None => {
+                let fraction = format!("{:04}", self.0.abs() % SCALE);
+                format_digits(self.0.abs() / SCALE, fraction.trim_end_matches('0'))
+            }
+        };
+        let is_nonnegative = self.0 >= 0 || digits.chars().all(|c| c == '0' || c == '.');
+        f.pad_integral(is_nonnegative, "", &digits)
+    }
+}
+
+// Written and read as a plain JSON number; four decimal places always survive the round trip.
+impl Serialize for Fixed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Fixed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Fixed, D::Error> {
+        f64::deserialize(deserializer).map(Fixed::new)
+    }
+}
+
+fn format_digits(whole: i64, fraction: &str) -> String {
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed::Fixed;
+
+    #[test]
+    fn new_rounds_literals_to_four_places() {
+        assert_eq!(Fixed::new(0.17), "0.17".parse().unwrap());
+        assert_eq!(Fixed::new(-0.25), "-0.25".parse().unwrap());
+        assert_eq!(Fixed::new(0.00004), Fixed::ZERO);
+    }
+
+    #[test]
+    fn multiplication_is_exact() {
+        // 0.17 * 262 in f32 is 44.539997
+        let result = Fixed::new(0.17) * 262;
+        assert_eq!(result.to_string(), "44.54");
+        assert_eq!(Fixed::new(0.17) * Fixed::from_int(262), result);
+    }
+
+    #[test]
+    fn division_rounds_to_nearest() {
+        let result = Fixed::from_int(262) / Fixed::from_int(6);
+        assert_eq!(result, Fixed::new(43.6667));
+    }
+
+    #[test]
+    fn round_to_quarter_goes_away_from_zero_at_halfway() {
+        let quarter = Fixed::new(0.25);
+        assert_eq!(Fixed::new(0.125).round_to(quarter), Fixed::new(0.25));
+        assert_eq!(Fixed::new(0.1249).round_to(quarter), Fixed::ZERO);
+        assert_eq!(Fixed::new(-0.125).round_to(quarter), Fixed::new(-0.25));
+    }
+
+    #[test]
+    fn ceil_to_quarter_only_moves_up() {
+        let quarter = Fixed::new(0.25);
+        assert_eq!(Fixed::new(0.2501).ceil_to(quarter), Fixed::new(0.5));
+        assert_eq!(Fixed::new(0.5).ceil_to(quarter), Fixed::new(0.5));
+        assert_eq!(Fixed::ZERO.ceil_to(quarter), Fixed::ZERO);
+    }
+
+    #[test]
+    fn display_uses_shortest_form_without_precision() {
+        assert_eq!(Fixed::new(4.5).to_string(), "4.5");
+        assert_eq!(Fixed::from_int(3).to_string(), "3");
+        assert_eq!(Fixed::new(-0.75).to_string(), "-0.75");
+    }
+
+    #[test]
+    fn display_honours_precision_and_sign() {
+        assert_eq!(format!("{:.2}", Fixed::new(4.4999)), "4.50");
+        assert_eq!(format!("{:.1}", Fixed::new(2.25)), "2.3");
+        assert_eq!(format!("{:.0}", Fixed::new(2891.4)), "2891");
+        assert_eq!(format!("{:.6}", Fixed::new(0.5)), "0.500000");
+        assert_eq!(format!("{:+.2}", Fixed::new(0.5)), "+0.50");
+        assert_eq!(format!("{:.2}", Fixed::new(-0.001)), "0.00");
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert!("abc".parse::<Fixed>().is_err());
+        assert!(".".parse::<Fixed>().is_err());
+        assert!("1.23456".parse::<Fixed>().is_err());
+        assert_eq!(".5".parse::<Fixed>().unwrap(), Fixed::new(0.5));
+        assert_eq!("3".parse::<Fixed>().unwrap(), Fixed::from_int(3));
+    }
+
+    #[test]
+    fn json_round_trip_is_exact() {
+        let value = Fixed::new(4.4999);
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "4.4999");
+        assert_eq!(serde_json::from_str::<Fixed>(&json).unwrap(), value);
+        assert_eq!(
+            serde_json::from_str::<Fixed>("3").unwrap(),
+            Fixed::from_int(3)
+        );
+    }
+}
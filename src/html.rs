@@ -0,0 +1,284 @@
+use crate::policy::render_policy;
+use crate::types::{Course, CourseAllocation};
+use std::fs;
+use std::path::Path;
+
+// A static site that works straight from the file system: index.html with a sortable table
+// of every course, one page per course with its full derivation, and parameters.html.
+pub fn write_site(directory: &str, allocations: &[(Course, CourseAllocation)]) {
+    let courses_directory = Path::new(directory).join("courses");
+    fs::create_dir_all(&courses_directory)
+        .unwrap_or_else(|e| panic!("Can't create directory {}: {}", directory, e));
+
+    let pages = page_names(allocations);
+    write_file(
+        &Path::new(directory).join("index.html"),
+        &render_index(allocations, &pages),
+    );
+    for ((c, allocation), page) in allocations.iter().zip(&pages) {
+        write_file(
+            &courses_directory.join(page),
+            &render_course_page(c, allocation),
+        );
+    }
+    write_file(
+        &Path::new(directory).join("parameters.html"),
+        &page(
+            "TA Allocation Parameters",
+            "",
+            &markdown_to_html(&render_policy()),
+        ),
+    );
+    println!(
+        "Wrote the report for {} courses to {}",
+        allocations.len(),
+        directory
+    );
+}
+
+fn write_file(path: &Path, contents: &str) {
+    fs::write(path, contents).unwrap_or_else(|e| panic!("Can't write {}: {}", path.display(), e));
+}
+
+// ECE150.html, or ECE150-2.html for a second row with the same code
+pub fn page_names(allocations: &[(Course, CourseAllocation)]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (c, _) in allocations {
+        let code = c.code().to_string();
+        let seen = names
+            .iter()
+            .filter(|n| **n == format!("{}.html", code) || n.starts_with(&format!("{}-", code)))
+            .count();
+        if seen == 0 {
+            names.push(format!("{}.html", code));
+        } else {
+            names.push(format!("{}-{}.html", code, seen + 1));
+        }
+    }
+    names
+}
+
+pub fn render_index(allocations: &[(Course, CourseAllocation)], pages: &[String]) -> String {
+    let mut rows = String::new();
+    for ((c, allocation), page_name) in allocations.iter().zip(pages) {
+        rows.push_str(&format!(
+            "<tr><td><a href=\"courses/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            page_name,
+            escape(&c.name),
+            escape(&c.instructor),
+            c.enrollment,
+            allocation.total,
+            allocation.total - allocation.lab_amount,
+            allocation.lab_amount,
+            escape(allocation.special_case_reason.unwrap_or(""))
+        ));
+    }
+    let body = format!(
+        "<p>Click a column heading to sort by it. The rules and numbers behind these allocations are on the <a href=\"parameters.html\">parameters page</a>.</p>
+<table class=\"sortable\">
+<thead><tr><th>Course</th><th>Instructor</th><th>Enrollment</th><th>TA Allocation</th><th>Lecture Fraction</th><th>Lab Fraction</th><th>Special Case</th></tr></thead>
+<tbody>
+{}</tbody>
+</table>
+<script>{}</script>",
+        rows, SORT_SCRIPT
+    );
+    page("TA Allocations", "", &body)
+}
+
+pub fn render_course_page(c: &Course, allocation: &CourseAllocation) -> String {
+    let mut body = format!(
+        "<ul>
+<li>Instructor: {}</li>
+<li>Enrollment: {}</li>
+<li>Sections: {} lecture, {} lab, {} tutorial</li>
+<li>Unit weight: {}</li>
+<li>TA allocation: {} (lecture {}, lab {})</li>
+",
+        escape(&c.instructor),
+        c.enrollment,
+        c.lec_sections,
+        c.lab_sections,
+        c.tutorial_sections(),
+        c.unit_weight,
+        allocation.total,
+        allocation.total - allocation.lab_amount,
+        allocation.lab_amount
+    );
+    if let Some(reason) = allocation.special_case_reason {
+        body.push_str(&format!("<li>Special case: {}</li>\n", escape(reason)));
+    }
+    body.push_str("</ul>\n<h2>How it was worked out</h2>\n<pre>");
+    body.push_str(&escape(&allocation.trace.join("\n")));
+    body.push_str("</pre>\n");
+    page(&c.name, "../", &body)
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>{style}</style>
+</head>
+<body>
+<nav><a href=\"{root}index.html\">Courses</a> · <a href=\"{root}parameters.html\">Parameters</a></nav>
+<h1>{title}</h1>
+{body}
+</body>
+</html>
+",
+        title = escape(title),
+        style = STYLE,
+        root = root,
+        body = body
+    )
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Just enough Markdown for the policy document: headings, bullets, tables and paragraphs.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut in_list = false;
+    let mut in_table = false;
+    for line in markdown.lines() {
+        if in_list && !line.starts_with("* ") {
+            html.push_str("</ul>\n");
+            in_list = false;
+        }
+        if in_table && !line.starts_with('|') {
+            html.push_str("</table>\n");
+            in_table = false;
+        }
+        if let Some(heading) = line.strip_prefix("## ") {
+            html.push_str(&format!("<h2>{}</h2>\n", escape(heading)));
+        } else if line.starts_with("# ") {
+            // The page already has the title
+        } else if let Some(item) = line.strip_prefix("* ") {
+            if !in_list {
+                html.push_str("<ul>\n");
+                in_list = true;
+            }
+            html.push_str(&format!("<li>{}</li>\n", escape(item)));
+        } else if line.starts_with("|---") {
+            // The separator under the header row
+        } else if line.starts_with('|') {
+            let tag = if in_table { "td" } else { "th" };
+            if !in_table {
+                html.push_str("<table>\n");
+                in_table = true;
+            }
+            let cells: Vec<String> = line
+                .trim_matches('|')
+                .split('|')
+                .map(|cell| format!("<{}>{}</{}>", tag, escape(cell.trim()), tag))
+                .collect();
+            html.push_str(&format!("<tr>{}</tr>\n", cells.concat()));
+        } else if !line.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", escape(line)));
+        }
+    }
+    if in_list {
+        html.push_str("</ul>\n");
+    }
+    if in_table {
+        html.push_str("</table>\n");
+    }
+    html
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; max-width: 70em; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+table.sortable th { cursor: pointer; background: #eee; }
+pre { background: #f6f6f6; padding: 1em; overflow-x: auto; }";
+
+// Sorts numerically when both cells are numbers, otherwise alphabetically; clicking the
+// same heading again reverses the order.
+const SORT_SCRIPT: &str =
+    "document.querySelectorAll('table.sortable th').forEach(function (th, column) {
+  th.addEventListener('click', function () {
+    var tbody = th.closest('table').querySelector('tbody');
+    var ascending = th.dataset.order !== 'asc';
+    th.closest('tr').querySelectorAll('th').forEach(function (h) { delete h.dataset.order; });
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    var rows = Array.from(tbody.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[column].textContent, y = b.cells[column].textContent;
+      var nx = parseFloat(x), ny = parseFloat(y);
+      var order = (!isNaN(nx) && !isNaN(ny)) ? nx - ny : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach(function (row) { tbody.appendChild(row); });
+  });
+});";
+
+#[cfg(test)]
+mod tests {
+    use crate::html::{escape, markdown_to_html, page_names, render_course_page, render_index};
+    use crate::types::calculated;
+
+    #[test]
+    fn escape_html_special_characters() {
+        assert_eq!(escape("<b>A & B</b>"), "&lt;b&gt;A &amp; B&lt;/b&gt;");
+    }
+
+    #[test]
+    fn page_names_are_unique() {
+        let allocations = vec![
+            calculated("ECE 150", "Ann Smith"),
+            calculated("ECE 250", "Bo Chen"),
+            calculated("ECE150", "Cy Doe"),
+        ];
+
+        assert_eq!(
+            page_names(&allocations),
+            vec!["ECE150.html", "ECE250.html", "ECE150-2.html"]
+        );
+    }
+
+    #[test]
+    fn index_links_every_course() {
+        let allocations = vec![
+            calculated("ECE 350", "Ann Smith"),
+            calculated("ECE 498A", "Bo Chen"),
+        ];
+        let index = render_index(&allocations, &page_names(&allocations));
+
+        assert!(index.contains("<a href=\"courses/ECE350.html\">ECE 350</a>"));
+        assert!(index.contains("<td>Capstone Course</td>"));
+        assert!(index.contains("<table class=\"sortable\">"));
+        assert!(index.contains("<a href=\"parameters.html\">"));
+    }
+
+    #[test]
+    fn course_page_has_the_derivation_and_special_case() {
+        let (c, allocation) = calculated("ECE 498A", "Bo Chen");
+
+        let html = render_course_page(&c, &allocation);
+
+        assert!(html.contains("<title>ECE 498A</title>"));
+        assert!(html.contains("<li>Special case: Capstone Course</li>"));
+        assert!(html.contains("Adding 24.00 hours for Midterm Marking"));
+        assert!(html.contains("Found special case for course ECE 498A of type NO_TA_ALLOC."));
+    }
+
+    #[test]
+    fn markdown_tables_and_lists_become_html() {
+        let html =
+            markdown_to_html("## Rules\n\n* One\n* Two\n\n| A | B |\n|---|---|\n| 1 | 2 |\n");
+
+        assert_eq!(
+            html,
+            "<h2>Rules</h2>\n<ul>\n<li>One</li>\n<li>Two</li>\n</ul>\n<table>\n<tr><th>A</th><th>B</th></tr>\n<tr><td>1</td><td>2</td></tr>\n</table>\n"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use crate::json;
+use crate::types::Course;
+
+// A .json file is read by json::read_courses_file instead.
+// The first six columns are always Course,Instructor,Enrollment,Course Sections,
+// Lab Sections,Unit Weight. Anything after that is optional and found by its header,
+// so extra columns can be added in any order (or not at all).
+pub struct InputColumns {
+    pub course_type: Option<usize>,
+    pub lab_only: Option<usize>,
+    pub lab_students_per_ta: Option<usize>,
+    pub lab_instructor: Option<usize>,
+    pub tut_sections: Option<usize>,
+    pub midterms: Option<usize>,
+    pub final_exam: Option<usize>,
+    pub assignments: Option<usize>,
+    pub delivery_mode: Option<usize>,
+}
+
+impl InputColumns {
+    pub fn from_headers(headers: &csv::StringRecord) -> InputColumns {
+        InputColumns {
+            course_type: find_column(headers, "Type"),
+            lab_only: find_column(headers, "Lab Only"),
+            lab_students_per_ta: find_column(headers, "Lab Ratio"),
+            lab_instructor: find_column(headers, "Lab Instructor"),
+            tut_sections: find_column(headers, "Tutorial Sections"),
+            midterms: find_column(headers, "Midterms"),
+            final_exam: find_column(headers, "Final Exam"),
+            assignments: find_column(headers, "Assignments"),
+            delivery_mode: find_column(headers, "Delivery Mode"),
+        }
+    }
+}
+
+// Spreadsheet exports sometimes start with a byte order mark, which sticks to the first header.
+pub fn find_column(headers: &csv::StringRecord, name: &str) -> Option<usize> {
+    headers.iter().position(|h| {
+        h.trim_start_matches('\u{feff}')
+            .trim()
+            .eq_ignore_ascii_case(name)
+    })
+}
+
+// An optional column counts as missing if it isn't in the file or the cell is blank.
+fn optional_field(record: &csv::StringRecord, column: Option<usize>) -> Option<&str> {
+    column
+        .and_then(|i| record.get(i))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_count(column: &str, value: &str) -> Result<i32, String> {
+    value.parse().ok().filter(|n| *n >= 0).ok_or_else(|| {
+        format!(
+            "Expected a whole number in {} column, not '{}'",
+            column, value
+        )
+    })
+}
+
+fn parse_yes_no(column: &str, value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Ok(true),
+        "no" | "n" | "false" | "0" => Ok(false),
+        _ => Err(format!(
+            "Expected yes or no in {} column, not '{}'",
+            column, value
+        )),
+    }
+}
+
+pub fn read_input_file(path: &String) -> Vec<Course> {
+    if path.ends_with(".json") {
+        return json::read_courses_file(path);
+    }
+    let mut courses: Vec<Course> = Vec::new();
+    let mut rdr = csv::Reader::from_path(path).unwrap();
+    let columns = InputColumns::from_headers(rdr.headers().unwrap());
+    for (row, result) in rdr.records().enumerate() {
+        let record = result.unwrap();
+        // Row 1 is the header
+        let course = parse_course_record(&record, &columns)
+            .unwrap_or_else(|e| panic!("{} (row {} of {})", e, row + 2, path));
+        courses.push(course);
+    }
+    courses
+}
+
+pub fn parse_course_record(
+    record: &csv::StringRecord,
+    columns: &InputColumns,
+) -> Result<Course, String> {
+    let name = record.get(0).unwrap().trim().to_string();
+    if let Err(e) = CourseCode::parse(&name) {
+        return Err(e.to_string());
+    }
+    let course_type = match optional_field(record, columns.course_type) {
+        Some(value) => Some(value.parse()?),
+        None => None,
+    };
+    let lab_only = match optional_field(record, columns.lab_only) {
+        Some(value) => Some(parse_yes_no("Lab Only", value)?),
+        None => None,
+    };
+    let lab_students_per_ta = match optional_field(record, columns.lab_students_per_ta) {
+        Some(value) => {
+            let ratio: Fixed = value
+                .parse()
+                .map_err(|e| format!("Bad value in Lab Ratio column: {}", e))?;
+            if ratio <= Fixed::ZERO {
+                return Err(format!("Lab Ratio must be more than 0, not {}", value));
+            }
+            Some(ratio)
+        }
+        None => None,
+    };
+    let lab_instructor = match optional_field(record, columns.lab_instructor) {
+        Some(value) => Some(parse_yes_no("Lab Instructor", value)?),
+        None => None,
+    };
+    let tut_sections = match optional_field(record, columns.tut_sections) {
+        Some(value) => Some(parse_count("Tutorial Sections", value)?),
+        None => None,
+    };
+    let midterms = match optional_field(record, columns.midterms) {
+        Some(value) => Some(parse_count("Midterms", value)?),
+        None => None,
+    };
+    let final_exam = match optional_field(record, columns.final_exam) {
+        Some(value) => Some(parse_yes_no("Final Exam", value)?),
+        None => None,
+    };
+    let assignments = match optional_field(record, columns.assignments) {
+        Some(value) => Some(parse_count("Assignments", value)?),
+        None => None,
+    };
+    let delivery_mode = match optional_field(record, columns.delivery_mode) {
+        Some(value) => Some(value.parse()?),
+        None => None,
+    };
+
+    Ok(Course {
+        name,
+        instructor: record.get(1).unwrap().trim().to_string(),
+        enrollment: record.get(2).unwrap().trim().parse().unwrap(),
+        lec_sections: record.get(3).unwrap().trim().parse().unwrap(),
+        lab_sections: record.get(4).unwrap().trim().parse().unwrap(),
+        unit_weight: record.get(5).unwrap().trim().parse().unwrap(),
+        course_type,
+        lab_only,
+        lab_students_per_ta,
+        lab_instructor,
+        tut_sections,
+        midterms,
+        final_exam,
+        assignments,
+        delivery_mode,
+        multi_instructor: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed::Fixed;
+    use crate::input::read_input_file;
+    use crate::types::CourseType::{GRAD, MENG};
+    use crate::types::DeliveryMode::{HYBRID, IN_PERSON, ONLINE};
+
+    #[test]
+    fn parse_input_file_with_type_column() {
+        let courses = read_input_file(&String::from("test_files/course_types.csv"));
+
+        assert_eq!(courses.len(), 3);
+        assert_eq!(courses.first().unwrap().course_type, Some(MENG));
+        assert_eq!(courses.get(1).unwrap().course_type, None);
+        assert_eq!(courses.get(2).unwrap().course_type, Some(GRAD));
+    }
+
+    #[test]
+    fn parse_input_file_with_lab_only_column() {
+        let courses = read_input_file(&String::from("test_files/lab_only.csv"));
+
+        assert_eq!(courses.len(), 4);
+        assert_eq!(courses.first().unwrap().lab_only, Some(true));
+        assert_eq!(courses.get(1).unwrap().lab_only, Some(false));
+        assert_eq!(courses.get(2).unwrap().lab_only, None);
+        assert_eq!(courses.get(3).unwrap().lab_only, Some(true));
+    }
+
+    #[test]
+    fn parse_input_file_with_lab_parameter_columns() {
+        let courses = read_input_file(&String::from("test_files/lab_parameters.csv"));
+
+        assert_eq!(courses.len(), 3);
+        assert_eq!(
+            courses.first().unwrap().lab_students_per_ta,
+            Some(Fixed::new(8.0))
+        );
+        assert_eq!(courses.first().unwrap().lab_instructor, Some(false));
+        assert_eq!(courses.get(1).unwrap().lab_students_per_ta, None);
+        assert_eq!(courses.get(1).unwrap().lab_instructor, Some(true));
+        assert_eq!(courses.get(2).unwrap().lab_students_per_ta, None);
+        assert_eq!(courses.get(2).unwrap().lab_instructor, None);
+    }
+
+    #[test]
+    fn parse_input_file_with_tutorial_sections_column() {
+        let courses = read_input_file(&String::from("test_files/tutorials.csv"));
+
+        assert_eq!(courses.len(), 2);
+        assert_eq!(courses.first().unwrap().tut_sections, Some(12));
+        assert_eq!(courses.first().unwrap().tutorial_sections(), 12);
+        assert_eq!(courses.get(1).unwrap().tut_sections, None);
+        assert_eq!(courses.get(1).unwrap().tutorial_sections(), 2);
+    }
+
+    #[test]
+    fn parse_input_file_with_assessment_columns() {
+        let courses = read_input_file(&String::from("test_files/assessments.csv"));
+
+        assert_eq!(courses.len(), 3);
+        assert_eq!(courses.first().unwrap().midterms, Some(2));
+        assert_eq!(courses.first().unwrap().final_exam, Some(false));
+        assert_eq!(courses.first().unwrap().assignments, Some(6));
+        assert_eq!(courses.get(1).unwrap().midterms, None);
+        assert_eq!(courses.get(1).unwrap().final_exam, None);
+        assert_eq!(courses.get(2).unwrap().midterms, Some(0));
+    }
+
+    #[test]
+    fn parse_input_file_with_delivery_mode_column() {
+        let courses = read_input_file(&String::from("test_files/delivery_modes.csv"));
+
+        assert_eq!(courses.len(), 3);
+        assert_eq!(courses.first().unwrap().delivery_mode, Some(ONLINE));
+        assert_eq!(courses.get(1).unwrap().delivery_mode, None);
+        assert_eq!(courses.get(1).unwrap().delivery_mode(), IN_PERSON);
+        assert_eq!(courses.get(2).unwrap().delivery_mode, Some(HYBRID));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected yes or no in Lab Only column, not 'maybe' (row 2")]
+    fn parse_input_file_with_bad_lab_only_value() {
+        read_input_file(&String::from("test_files/bad_lab_only.csv"));
+    }
+}
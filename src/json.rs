@@ -0,0 +1,205 @@
+use crate::calculator::find_special_case;
+use crate::configuration::ROUNDING_POLICY;
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use crate::types::{AllocationComponent, Course, CourseAllocation, RoundingPolicy, SpecialCase};
+use serde::Serialize;
+use std::fs;
+
+// The version of schema/courses.v1.schema.json and schema/allocations.v1.schema.json.
+// Anything that changes the meaning of a field or takes one away needs a new version and
+// new schema files; adding an optional input field or an output field doesn't.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct AllocationsDocument<'a> {
+    pub schema_version: u32,
+    pub crate_version: &'static str,
+    pub rounding: RoundingPolicy,
+    pub allocations: Vec<AllocationRecord<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct AllocationRecord<'a> {
+    pub course: &'a Course,
+    pub total: Fixed,
+    pub lecture_amount: Fixed,
+    pub lab_amount: Fixed,
+    pub components: &'a [AllocationComponent],
+    pub special_case: Option<&'static SpecialCase>,
+}
+
+// A JSON array of courses, checked the same way as the rows of a CSV file.
+pub fn read_courses_file(path: &str) -> Vec<Course> {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("Can't read {}: {}", path, e));
+    read_courses(&text).unwrap_or_else(|e| panic!("{} (in {})", e, path))
+}
+
+pub fn read_courses(text: &str) -> Result<Vec<Course>, String> {
+    let courses: Vec<Course> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    for (index, c) in courses.iter().enumerate() {
+        check_fields(c).map_err(|e| format!("{} (course {})", e, index + 1))?;
+    }
+    Ok(courses)
+}
+
+// What the CSV reader rejects outright: a course code that doesn't parse, and a lab ratio
+// that would be divided by.
+pub fn check_fields(c: &Course) -> Result<(), String> {
+    CourseCode::parse(&c.name).map_err(|e| e.to_string())?;
+    if let Some(ratio) = c.lab_students_per_ta {
+        if ratio <= Fixed::ZERO {
+            return Err(format!(
+                "lab_students_per_ta must be more than 0, not {}",
+                ratio
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn allocations_document(allocations: &[(Course, CourseAllocation)]) -> AllocationsDocument<'_> {
+    AllocationsDocument {
+        schema_version: SCHEMA_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION"),
+        rounding: ROUNDING_POLICY,
+        allocations: allocations
+            .iter()
+            .map(|(c, allocation)| AllocationRecord {
+                course: c,
+                total: allocation.total,
+                lecture_amount: allocation.total - allocation.lab_amount,
+                lab_amount: allocation.lab_amount,
+                components: &allocation.components,
+                special_case: allocation
+                    .special_case_reason
+                    .and_then(|_| find_special_case(&c.code())),
+            })
+            .collect(),
+    }
+}
+
+pub fn write_allocations(path: &str, allocations: &[(Course, CourseAllocation)]) {
+    let json = serde_json::to_string_pretty(&allocations_document(allocations)).unwrap();
+    fs::write(path, json + "\n").unwrap_or_else(|e| panic!("Can't write {}: {}", path, e));
+    println!("Wrote {} allocations to {}", allocations.len(), path);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{calculate_ta_hours, check_for_special_case};
+    use crate::fixed::Fixed;
+    use crate::json::{allocations_document, read_courses, read_courses_file, SCHEMA_VERSION};
+    use crate::types::{Course, DeliveryMode};
+    use serde_json::Value;
+
+    const INPUT_SCHEMA: &str = include_str!("../schema/courses.v1.schema.json");
+    const OUTPUT_SCHEMA: &str = include_str!("../schema/allocations.v1.schema.json");
+
+    fn check_against_schema(schema: &str, instance: &Value) {
+        let schema: Value = serde_json::from_str(schema).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let errors: Vec<String> = validator
+            .iter_errors(instance)
+            .map(|e| format!("{} at {}", e, e.instance_path()))
+            .collect();
+        assert!(errors.is_empty(), "{}", errors.join("\n"));
+    }
+
+    fn read_sample(path: &str) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn calculated(courses: Vec<Course>) -> Vec<(Course, crate::types::CourseAllocation)> {
+        courses
+            .into_iter()
+            .map(|c| {
+                let allocation = check_for_special_case(&c, calculate_ta_hours(&c));
+                (c, allocation)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sample_input_matches_schema() {
+        check_against_schema(INPUT_SCHEMA, &read_sample("test_files/courses.json"));
+    }
+
+    #[test]
+    fn sample_output_matches_schema() {
+        check_against_schema(OUTPUT_SCHEMA, &read_sample("test_files/allocations.json"));
+    }
+
+    #[test]
+    fn schema_version_matches_the_schema_files() {
+        let schema: Value = serde_json::from_str(OUTPUT_SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        let id = format!(":v{}", SCHEMA_VERSION);
+        assert!(schema["$id"].as_str().unwrap().ends_with(&id));
+        let schema: Value = serde_json::from_str(INPUT_SCHEMA).unwrap();
+        assert!(schema["$id"].as_str().unwrap().ends_with(&id));
+    }
+
+    #[test]
+    fn read_sample_input() {
+        let courses = read_courses_file("test_files/courses.json");
+
+        assert_eq!(courses.len(), 4);
+        assert_eq!(courses[0].name, "ECE 150");
+        assert_eq!(courses[0].unit_weight, Fixed::new(0.5));
+        assert_eq!(courses[0].lab_sections, 2);
+        assert_eq!(courses[1].lab_sections, 0);
+        assert_eq!(courses[1].delivery_mode, Some(DeliveryMode::ONLINE));
+        assert_eq!(courses[1].tut_sections, None);
+    }
+
+    #[test]
+    fn written_output_matches_schema() {
+        let allocations = calculated(read_courses_file("test_files/courses.json"));
+
+        let json = serde_json::to_value(allocations_document(&allocations)).unwrap();
+
+        check_against_schema(OUTPUT_SCHEMA, &json);
+        assert_eq!(json["allocations"][3]["course"]["name"], "ECE 498A");
+        assert_eq!(
+            json["allocations"][3]["special_case"]["reason"],
+            "Capstone Course"
+        );
+        assert_eq!(
+            json["allocations"][3]["special_case"]["allocation_rule"],
+            "NO_TA_ALLOC"
+        );
+        assert_eq!(json["allocations"][0]["special_case"], Value::Null);
+        let split = json["allocations"][0]["lecture_amount"].as_f64().unwrap()
+            + json["allocations"][0]["lab_amount"].as_f64().unwrap();
+        assert_eq!(split, json["allocations"][0]["total"].as_f64().unwrap());
+    }
+
+    #[test]
+    fn written_course_reads_back() {
+        let allocations = calculated(read_courses_file("test_files/courses.json"));
+        let mut course = serde_json::to_value(&allocations[1].0).unwrap();
+        course["enrollment"] = Value::from(200);
+        course["multi_instructor"] = Value::from(true);
+
+        let courses = read_courses(&Value::Array(vec![course]).to_string()).unwrap();
+
+        assert_eq!(courses[0].name, "ECE 459");
+        assert_eq!(courses[0].enrollment, 200);
+        assert_eq!(courses[0].delivery_mode, Some(DeliveryMode::ONLINE));
+        assert_eq!(courses[0].assignments, Some(3));
+        assert!(!courses[0].multi_instructor);
+    }
+
+    #[test]
+    fn read_rejects_bad_courses() {
+        assert!(read_courses("{}").is_err());
+        assert!(read_courses(r#"[{"name": "150", "enrollment": 10}]"#).is_err());
+        assert!(read_courses(r#"[{"name": "ECE 150", "enrolment": 10}]"#).is_err());
+        assert!(read_courses(r#"[{"name": "ECE 150", "lab_students_per_ta": 0}]"#).is_err());
+        assert!(read_courses(r#"[{"name": "ECE 150", "enrollment": 10}]"#).is_ok());
+    }
+}
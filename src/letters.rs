@@ -0,0 +1,225 @@
+use crate::configuration::{FULL_TA_HOURS, LETTER_COURSE_TEMPLATE, LETTER_TEMPLATE};
+use crate::report::split_instructors;
+use crate::types::{Course, CourseAllocation};
+use std::fs;
+use std::path::Path;
+
+// Writes one Markdown letter per instructor into the directory, for review before sending.
+// There's nobody to address for a course with no instructor, so those are only listed.
+pub fn write_letters(directory: &str, allocations: &[(Course, CourseAllocation)]) {
+    fs::create_dir_all(directory)
+        .unwrap_or_else(|e| panic!("Can't create directory {}: {}", directory, e));
+    let letters = group_letters(allocations);
+    let names = file_names(&letters);
+    for ((recipient, courses), name) in letters.iter().zip(&names) {
+        let path = Path::new(directory).join(name);
+        fs::write(&path, render_letter(recipient, courses))
+            .unwrap_or_else(|e| panic!("Can't write {}: {}", path.display(), e));
+    }
+    println!("Wrote {} letters to {}", letters.len(), directory);
+    let unaddressed: Vec<&str> = allocations
+        .iter()
+        .filter(|(c, _)| split_instructors(&c.instructor).is_empty())
+        .map(|(c, _)| c.name.as_str())
+        .collect();
+    if !unaddressed.is_empty() {
+        println!(
+            "No letter for courses with no instructor: {}",
+            unaddressed.join(", ")
+        );
+    }
+}
+
+pub fn group_letters(
+    allocations: &[(Course, CourseAllocation)],
+) -> Vec<(String, Vec<&(Course, CourseAllocation)>)> {
+    let mut letters: Vec<(String, Vec<&(Course, CourseAllocation)>)> = Vec::new();
+    for entry in allocations {
+        for recipient in split_instructors(&entry.0.instructor) {
+            match letters.iter_mut().find(|(r, _)| *r == recipient) {
+                Some((_, courses)) => courses.push(entry),
+                None => letters.push((recipient, vec![entry])),
+            }
+        }
+    }
+    letters
+}
+
+pub fn render_letter(recipient: &str, courses: &[&(Course, CourseAllocation)]) -> String {
+    let course_sections: Vec<String> = courses
+        .iter()
+        .map(|(c, allocation)| render_course(recipient, c, allocation))
+        .collect();
+    fill(
+        LETTER_TEMPLATE,
+        &[
+            ("instructor", recipient.to_string()),
+            ("courses", course_sections.join("\n")),
+        ],
+    )
+}
+
+fn render_course(recipient: &str, c: &Course, allocation: &CourseAllocation) -> String {
+    let others: Vec<String> = split_instructors(&c.instructor)
+        .into_iter()
+        .filter(|i| i != recipient)
+        .collect();
+    let co_instructors = if others.is_empty() {
+        String::new()
+    } else {
+        format!("* Co-taught with: {}\n", others.join(", "))
+    };
+    let special_case = match allocation.special_case_reason {
+        Some(reason) => format!("* Special case: {}\n", reason),
+        None => String::new(),
+    };
+    let breakdown: Vec<String> = allocation
+        .components
+        .iter()
+        .map(|x| format!("* {}: {:.2} hours ({:?})", x.name, x.hours, x.calc_rule))
+        .collect();
+    let breakdown = if breakdown.is_empty() {
+        String::from("* No hours from the standard rules.")
+    } else {
+        breakdown.join("\n")
+    };
+    fill(
+        LETTER_COURSE_TEMPLATE,
+        &[
+            ("course", c.name.clone()),
+            ("enrollment", c.enrollment.to_string()),
+            ("allocation", allocation.total.to_string()),
+            (
+                "lecture",
+                (allocation.total - allocation.lab_amount).to_string(),
+            ),
+            ("lab", allocation.lab_amount.to_string()),
+            ("co_instructors", co_instructors),
+            ("special_case", special_case),
+            ("full_ta_hours", FULL_TA_HOURS.to_string()),
+            ("breakdown", breakdown),
+        ],
+    )
+}
+
+// Replaces each {name} in the template with its value.
+pub fn fill(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+// Ann-Smith.md, or Ann-Smith-2.md for a second recipient whose name comes out the same.
+pub fn file_names(letters: &[(String, Vec<&(Course, CourseAllocation)>)]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (recipient, _) in letters {
+        let mut base = file_name(recipient);
+        if base.is_empty() {
+            base = String::from("letter");
+        }
+        let mut name = format!("{}.md", base);
+        let mut n = 1;
+        while names.contains(&name) {
+            n += 1;
+            name = format!("{}-{}.md", base, n);
+        }
+        names.push(name);
+    }
+    names
+}
+
+// "Ann Smith" -> "Ann-Smith"; anything that isn't a letter or digit becomes a hyphen.
+fn file_name(recipient: &str) -> String {
+    let name: String = recipient
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { '-' })
+        .collect();
+    name.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::letters::{file_name, file_names, fill, group_letters, render_letter};
+    use crate::types::calculated;
+
+    #[test]
+    fn fill_replaces_every_placeholder() {
+        let text = fill(
+            "{a} and {b}, then {a} again",
+            &[("a", String::from("one")), ("b", String::from("two"))],
+        );
+
+        assert_eq!(text, "one and two, then one again");
+    }
+
+    #[test]
+    fn file_names_are_safe() {
+        assert_eq!(file_name("Ann Smith"), "Ann-Smith");
+        assert_eq!(file_name("O'Brien, Pat"), "O-Brien-Pat");
+        assert_eq!(file_name("ECE 498A"), "ECE-498A");
+    }
+
+    #[test]
+    fn co_taught_courses_go_in_each_letter() {
+        let allocations = vec![
+            calculated("ECE 350", "Ann Smith / Bo Chen"),
+            calculated("ECE 250", "Bo Chen"),
+            calculated("ECE 498A", ""),
+        ];
+
+        let letters = group_letters(&allocations);
+
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].0, "Ann Smith");
+        assert_eq!(letters[0].1.len(), 1);
+        assert_eq!(letters[1].0, "Bo Chen");
+        assert_eq!(letters[1].1.len(), 2);
+    }
+
+    #[test]
+    fn file_names_are_unique() {
+        let allocations = vec![
+            calculated("ECE 350", "Ann Smith"),
+            calculated("ECE 250", "Ann-Smith"),
+            calculated("ECE 498A", "Ann Smith!"),
+            calculated("ECE 498B", "???"),
+        ];
+        let letters = group_letters(&allocations);
+
+        assert_eq!(
+            file_names(&letters),
+            vec![
+                "Ann-Smith.md",
+                "Ann-Smith-2.md",
+                "Ann-Smith-3.md",
+                "letter.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn letter_has_allocation_breakdown_and_special_case() {
+        let allocations = vec![
+            calculated("ECE 350", "Ann Smith; Bo Chen"),
+            calculated("ECE 498A", "Ann Smith"),
+        ];
+        let letters = group_letters(&allocations);
+
+        let letter = render_letter(&letters[0].0, &letters[0].1);
+
+        assert!(letter.starts_with("Dear Ann Smith,"));
+        assert!(letter.contains("## ECE 350"));
+        assert!(letter.contains("* Enrollment: 120"));
+        assert!(letter.contains("* Co-taught with: Bo Chen\n"));
+        assert!(letter.contains("* Midterm Marking: 24.00 hours (PER_STUDENT)"));
+        assert!(letter.contains("## ECE 498A"));
+        assert!(letter.contains("* TA allocation: 0 (lecture 0, lab 0)"));
+        assert!(letter.contains("* Special case: Capstone Course\n"));
+        assert!(!letter.contains('{'));
+    }
+}
@@ -1,22 +1,302 @@
 #![allow(clippy::upper_case_acronyms, non_camel_case_types)]
 
 use crate::calculator::check_for_special_case;
-use crate::types::{Course, CourseAllocation};
+use crate::configuration::{DUPLICATE_POLICY, LETTERS_DIRECTORY, ROUNDING_POLICY};
+use crate::input::read_input_file;
+use crate::types::{Course, CourseAllocation, DuplicatePolicy};
 use std::env;
 
 mod calculator;
+mod calibration;
 mod configuration;
+mod coursecode;
+mod duplicates;
+mod fixed;
+mod html;
+mod input;
+mod json;
+mod letters;
+mod manifest;
+mod policy;
+mod report;
+mod sections;
+mod server;
 mod specialcases;
+mod store;
 mod types;
+mod validation;
+
+const OUTPUT_FILE: &str = "TA-Allocations.csv";
+const MANIFEST_FILE: &str = "TA-Allocations.manifest.json";
+const POLICY_FILE: &str = "TA-Policy.md";
+const HTML_DIRECTORY: &str = "TA-Report";
+const JSON_FILE: &str = "TA-Allocations.json";
+const DATABASE_FILE: &str = "TA-History.sqlite";
+const SERVE_ADDRESS: &str = "127.0.0.1:8080";
 
 fn main() {
+    let (args, duplicate_policy) =
+        duplicates::duplicate_policy_from_args(env::args().collect(), DUPLICATE_POLICY);
+    let (args, term) = take_option(args, "--term");
+    let (args, database) = take_option(args, "--database");
+    let database = database.unwrap_or(DATABASE_FILE.to_string());
+    if args.get(1).map(String::as_str) == Some("calibrate") {
+        calibration::calibrate(args.get(2).unwrap());
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("validate") {
+        let courses = read_input_file(args.get(2).unwrap());
+        let warnings = validation::validate(&courses);
+        for warning in &warnings {
+            println!("Warning: {}", warning);
+        }
+        if warnings.is_empty() {
+            println!("No problems found in {} courses.", courses.len());
+        } else {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("letters") {
+        let courses = read_input_file(args.get(2).unwrap());
+        let ta_hours = calculate(courses, duplicate_policy);
+        let directory = args.get(3).map(String::as_str).unwrap_or(LETTERS_DIRECTORY);
+        letters::write_letters(directory, &ta_hours);
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("html") {
+        let courses = read_input_file(args.get(2).unwrap());
+        let ta_hours = calculate(courses, duplicate_policy);
+        let directory = args.get(3).map(String::as_str).unwrap_or(HTML_DIRECTORY);
+        html::write_site(directory, &ta_hours);
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("json") {
+        let courses = read_input_file(args.get(2).unwrap());
+        let ta_hours = calculate(courses, duplicate_policy);
+        json::write_allocations(
+            args.get(3).map(String::as_str).unwrap_or(JSON_FILE),
+            &ta_hours,
+        );
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("serve") {
+        let courses = read_input_file(args.get(2).unwrap());
+        let ta_hours = calculate(courses, duplicate_policy);
+        let address = args.get(3).map(String::as_str).unwrap_or(SERVE_ADDRESS);
+        server::serve(address, &ta_hours);
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("import") {
+        let manifest_file = args.get(2).map(String::as_str).unwrap_or(MANIFEST_FILE);
+        let recorded = manifest::read_manifest(manifest_file);
+        let term = term
+            .or(Some(recorded.term.clone()).filter(|t| !t.is_empty()))
+            .expect("import needs a term, e.g. --term=2027W");
+        let (ta_hours, problems) = reproduce(&recorded);
+        if !problems.is_empty() {
+            println!("Not importing the run from {}:", recorded.timestamp);
+            for problem in &problems {
+                println!("  {}", problem);
+            }
+            std::process::exit(1);
+        }
+        let mut conn = store::open_database(&database);
+        store::import_term(&mut conn, &term, &recorded, &ta_hours)
+            .unwrap_or_else(|e| panic!("{}", e));
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("history") {
+        let code =
+            coursecode::CourseCode::parse(args.get(2).unwrap()).unwrap_or_else(|e| panic!("{}", e));
+        let conn = store::open_database(&database);
+        store::print_history(&code, &store::course_history(&conn, &code));
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("export") {
+        let term = args.get(2).unwrap();
+        let default_path = format!("TA-Allocations-{}.csv", term);
+        let path = args.get(3).unwrap_or(&default_path);
+        let conn = store::open_database(&database);
+        let count = store::export_term(&conn, term, path).unwrap_or_else(|e| panic!("{}", e));
+        println!("Wrote {} courses for term {} to {}", count, term, path);
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("override") {
+        let term = args.get(2).unwrap();
+        let code =
+            coursecode::CourseCode::parse(args.get(3).unwrap()).unwrap_or_else(|e| panic!("{}", e));
+        let allocation: fixed::Fixed = args
+            .get(4)
+            .unwrap()
+            .parse()
+            .unwrap_or_else(|e| panic!("Bad allocation: {}", e));
+        let reason = args[5..].join(" ");
+        let conn = store::open_database(&database);
+        store::add_override(&conn, term, &code, allocation, &reason)
+            .unwrap_or_else(|e| panic!("{}", e));
+        println!("Set {} in term {} to {} TAs.", code, term, allocation);
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("policy") {
+        let path = args.get(2).map(String::as_str).unwrap_or(POLICY_FILE);
+        std::fs::write(path, policy::render_policy())
+            .unwrap_or_else(|e| panic!("Can't write {}: {}", path, e));
+        println!("Wrote the allocation policy to {}", path);
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("verify") {
+        if !verify(args.get(2).unwrap()) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let (input_format, input_file) = if args.get(1).map(String::as_str) == Some("sections") {
+        ("sections", args.get(2).unwrap())
+    } else {
+        ("calculate", args.get(1).unwrap())
+    };
+    let ta_hours = calculate(read_courses(input_format, input_file), duplicate_policy);
+    write_output(OUTPUT_FILE, &ta_hours);
+    report::write_instructor_report(
+        "TA-Allocations-By-Instructor.csv",
+        &report::summarize_by_instructor(&ta_hours),
+    );
+    manifest::write_manifest(
+        MANIFEST_FILE,
+        &manifest::Manifest {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            term: term.unwrap_or_default(),
+            timestamp: manifest::timestamp_now(),
+            input_format: input_format.to_string(),
+            input_file: input_file.clone(),
+            input_sha256: manifest::sha256_file(input_file),
+            duplicate_policy,
+            output_file: OUTPUT_FILE.to_string(),
+            output_sha256: manifest::sha256_file(OUTPUT_FILE),
+            configuration: manifest::effective_configuration(),
+        },
+    );
+}
+
+// Picks out --name=value from the command line, if it's there, and returns the other arguments.
+fn take_option(args: Vec<String>, name: &str) -> (Vec<String>, Option<String>) {
+    let prefix = format!("{}=", name);
+    let mut value = None;
+    let mut rest: Vec<String> = Vec::new();
+    for arg in args {
+        match arg.strip_prefix(&prefix) {
+            Some(v) => value = Some(v.to_string()),
+            None => rest.push(arg),
+        }
+    }
+    (rest, value)
+}
+
+fn read_courses(input_format: &str, input_file: &String) -> Vec<Course> {
+    match input_format {
+        "sections" => sections::read_sections_file(input_file),
+        _ => read_input_file(input_file),
+    }
+}
+
+// Re-runs the calculation recorded in a manifest and checks that the output comes out the
+// same. Returns false (after saying why) if it doesn't.
+fn verify(manifest_file: &str) -> bool {
+    let recorded = manifest::read_manifest(manifest_file);
+    let mut problems: Vec<String> = Vec::new();
+
+    if recorded.crate_version != env!("CARGO_PKG_VERSION") {
+        problems.push(format!(
+            "The run used version {}, this is version {}.",
+            recorded.crate_version,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+    let changed = manifest::configuration_differences(
+        &recorded.configuration,
+        &manifest::effective_configuration(),
+    );
+    if !changed.is_empty() {
+        problems.push(format!(
+            "The configuration has changed since the run: {}.",
+            changed.join(", ")
+        ));
+    }
+    let (_, output_problems) = reproduce(&recorded);
+    problems.extend(output_problems);
+
+    let label = if recorded.term.is_empty() {
+        String::from("the run")
+    } else {
+        format!("the {} run", recorded.term)
+    };
+    if problems.is_empty() {
+        println!(
+            "Verified {} from {}: the output is identical.",
+            label, recorded.timestamp
+        );
+        true
+    } else {
+        println!("Could not verify {} from {}:", label, recorded.timestamp);
+        for problem in &problems {
+            println!("  {}", problem);
+        }
+        false
+    }
+}
+
+// Calculates the run recorded in a manifest again. Any problems mean the result isn't the
+// output that was recorded, whatever version or configuration produced it.
+fn reproduce(recorded: &manifest::Manifest) -> (Vec<(Course, CourseAllocation)>, Vec<String>) {
+    let mut problems: Vec<String> = Vec::new();
+    let input_sha256 = manifest::sha256_file(&recorded.input_file);
+    if input_sha256 != recorded.input_sha256 {
+        problems.push(format!(
+            "Input file {} has changed since the run.",
+            recorded.input_file
+        ));
+    }
+
+    let ta_hours = calculate(
+        read_courses(&recorded.input_format, &recorded.input_file),
+        recorded.duplicate_policy,
+    );
+    let output_file = env::temp_dir().join(format!("ta-verify-{}.csv", std::process::id()));
+    let output_file = output_file.to_str().unwrap();
+    write_output(output_file, &ta_hours);
+    let output_sha256 = manifest::sha256_file(output_file);
+    std::fs::remove_file(output_file).ok();
+    if output_sha256 != recorded.output_sha256 {
+        problems.push(format!(
+            "The output is different from {} as it was recorded.",
+            recorded.output_file
+        ));
+    }
+    (ta_hours, problems)
+}
+
+// Everything between reading the input and writing the results, shared by the commands.
+fn calculate(
+    courses: Vec<Course>,
+    duplicate_policy: DuplicatePolicy,
+) -> Vec<(Course, CourseAllocation)> {
     println!("Calculating TA hours for courses...");
-    let args: Vec<String> = env::args().collect();
-    let courses = read_input_file(args.get(1).unwrap());
+    println!("Rounding policy: {}", ROUNDING_POLICY);
+    let courses = duplicates::resolve_duplicates(courses, duplicate_policy)
+        .unwrap_or_else(|e| panic!("{}", e));
     println!("Found {} courses to evaluate.", courses.len());
-
-    let ta_hours = calculate_ta_hours_for_courses(courses);
-    write_output(ta_hours);
+    for warning in validation::validate(&courses) {
+        println!("Warning: {}", warning);
+    }
+    calculate_ta_hours_for_courses(courses)
 }
 
 fn calculate_ta_hours_for_courses(courses: Vec<Course>) -> Vec<(Course, CourseAllocation)> {
@@ -29,8 +309,8 @@ fn calculate_ta_hours_for_courses(courses: Vec<Course>) -> Vec<(Course, CourseAl
     result
 }
 
-fn write_output(courses: Vec<(Course, CourseAllocation)>) {
-    let mut wtr = csv::Writer::from_path("TA-Allocations.csv").unwrap();
+fn write_output(path: &str, courses: &[(Course, CourseAllocation)]) {
+    let mut wtr = csv::Writer::from_path(path).unwrap();
     wtr.write_record([
         "Course",
         "Instructor",
@@ -38,44 +318,33 @@ fn write_output(courses: Vec<(Course, CourseAllocation)>) {
         "TA Allocation",
         "Lecture Fraction",
         "Lab Fraction",
+        "Rounding",
+        "Multi-Instructor",
     ])
     .unwrap();
 
     for c in courses {
         wtr.write_record(&[
-            c.0.name,
-            c.0.instructor,
+            c.0.name.clone(),
+            c.0.instructor.clone(),
             c.0.enrollment.to_string(),
             c.1.total.to_string(),
-            format!("{:.2}", c.1.total - c.1.lab_amount), // TODO Fix hack
+            format!("{:.2}", c.1.total - c.1.lab_amount),
             c.1.lab_amount.to_string(),
+            ROUNDING_POLICY.to_string(),
+            if c.0.multi_instructor { "yes" } else { "no" }.to_string(),
         ])
         .unwrap();
     }
 }
 
-fn read_input_file(path: &String) -> Vec<Course> {
-    let mut courses: Vec<Course> = Vec::new();
-    let mut rdr = csv::Reader::from_path(path).unwrap();
-    for result in rdr.records() {
-        let record = result.unwrap();
-        let course = Course {
-            name: record.get(0).unwrap().trim().to_string(),
-            instructor: record.get(1).unwrap().trim().to_string(),
-            enrollment: record.get(2).unwrap().trim().parse().unwrap(),
-            lec_sections: record.get(3).unwrap().trim().parse().unwrap(),
-            lab_sections: record.get(4).unwrap().trim().parse().unwrap(),
-            unit_weight: record.get(5).unwrap().trim().parse().unwrap(),
-        };
-        courses.push(course);
-    }
-    courses
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::calculator::calculate_ta_hours;
-    use crate::{calculate_ta_hours_for_courses, read_input_file};
+    use crate::calculate_ta_hours_for_courses;
+    use crate::fixed::Fixed;
+    use crate::input::read_input_file;
+    use crate::types::calculated;
+    use crate::write_output;
 
     #[test]
     fn parse_example_input_file() {
@@ -89,7 +358,9 @@ mod tests {
         assert_eq!(c.enrollment, 450);
         assert_eq!(c.lec_sections, 3);
         assert_eq!(c.lab_sections, 3);
-        assert_eq!(c.unit_weight, 1.0);
+        assert_eq!(c.unit_weight, Fixed::new(1.0));
+        assert_eq!(c.course_type, None);
+        assert_eq!(c.lab_only, None);
     }
 
     #[test]
@@ -106,6 +377,14 @@ mod tests {
         assert_eq!(courses.get(1).unwrap().lab_sections, 0);
     }
 
+    #[test]
+    #[should_panic(
+        expected = "Invalid course code 'Capstone': there is no catalog number after the subject (row 3"
+    )]
+    fn parse_input_file_with_malformed_course_code() {
+        read_input_file(&String::from("test_files/bad_course_code.csv"));
+    }
+
     #[test]
     fn calculate_ta_hours_for_course_with_lab() {
         let course_name = String::from("ECE150");
@@ -115,16 +394,34 @@ mod tests {
             enrollment: 450,
             lec_sections: 2,
             lab_sections: 1,
-            unit_weight: 1.0,
+            unit_weight: Fixed::new(1.0),
+            ..Default::default()
         };
         let outcome = calculate_ta_hours_for_courses(vec![course]);
 
         assert_eq!(outcome.len(), 1);
-        assert_eq!(outcome.first().unwrap().1.total, 7.75);
-        assert_eq!(outcome.first().unwrap().1.lab_amount, 4.5);
+        assert_eq!(outcome.first().unwrap().1.total, Fixed::new(7.75));
+        assert_eq!(outcome.first().unwrap().1.lab_amount, Fixed::new(4.5));
         assert_eq!(outcome.first().unwrap().0.name, "ECE150");
         assert_eq!(outcome.first().unwrap().0.enrollment, 450);
         assert_eq!(outcome.first().unwrap().0.lab_sections, 1);
-        assert_eq!(outcome.first().unwrap().0.unit_weight, 1.0);
+        assert_eq!(outcome.first().unwrap().0.unit_weight, Fixed::new(1.0));
+    }
+
+    #[test]
+    fn lecture_fraction_keeps_two_decimal_places() {
+        let output_file =
+            std::env::temp_dir().join(format!("ta-output-{}.csv", std::process::id()));
+        let output_file = output_file.to_str().unwrap();
+
+        write_output(output_file, &[calculated("ECE 498A", "Ann Smith")]);
+
+        let output = std::fs::read_to_string(output_file).unwrap();
+        std::fs::remove_file(output_file).ok();
+        assert!(output
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("ECE 498A,Ann Smith,120,0,0.00,0,"));
     }
 }
@@ -0,0 +1,184 @@
+use crate::configuration::{
+    DELIVERY_MODE_ADJUSTMENTS, FIRST_YEAR_UPLIFT, FULL_TA_HOURS, GRADUATE_COURSE,
+    LAB_INSTRUCTOR_ADJUSTMENT, LAB_RATIO_DENOMINATOR, MENG_COURSE,
+    MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD, MIN_ENROLLMENT_FOR_TA_ALLOC_MENG,
+    MIN_ENROLLMENT_FOR_TA_ALLOC_PD, MIN_ENROLLMENT_FOR_TA_ALLOC_UG, MIN_TA_THRESHOLD,
+    PROFESSIONAL_DEV_COURSE, ROUNDING_POLICY, STANDARD_ASSESSMENTS, UNDERGRADUATE_COURSE,
+    UNIT_WEIGHT_BASELINE,
+};
+use crate::specialcases::{LAB_ONLY_COURSES, LAB_PROFILES, SPECIAL_CASES};
+use crate::types::DuplicatePolicy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Everything needed to reproduce a published allocation file: what was run, on which
+// input, with which configuration, and what came out.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Manifest {
+    pub crate_version: String,
+    pub term: String,
+    pub timestamp: String,
+    // "calculate" for the usual input format or "sections" for a registrar export
+    pub input_format: String,
+    pub input_file: String,
+    pub input_sha256: String,
+    pub duplicate_policy: DuplicatePolicy,
+    pub output_file: String,
+    pub output_sha256: String,
+    pub configuration: Value,
+}
+
+// Every setting that can change an allocation, keyed by the name it has in the source.
+// The letter templates and calibration settings don't affect the numbers, so they're left out.
+pub fn effective_configuration() -> Value {
+    json!({
+        "FULL_TA_HOURS": FULL_TA_HOURS,
+        "MIN_TA_THRESHOLD": MIN_TA_THRESHOLD,
+        "ROUNDING_POLICY": ROUNDING_POLICY,
+        "LAB_RATIO_DENOMINATOR": LAB_RATIO_DENOMINATOR,
+        "LAB_INSTRUCTOR_ADJUSTMENT": LAB_INSTRUCTOR_ADJUSTMENT,
+        "FIRST_YEAR_UPLIFT": FIRST_YEAR_UPLIFT,
+        "UNIT_WEIGHT_BASELINE": UNIT_WEIGHT_BASELINE,
+        "MIN_ENROLLMENT_FOR_TA_ALLOC_UG": MIN_ENROLLMENT_FOR_TA_ALLOC_UG,
+        "MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD": MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD,
+        "MIN_ENROLLMENT_FOR_TA_ALLOC_PD": MIN_ENROLLMENT_FOR_TA_ALLOC_PD,
+        "MIN_ENROLLMENT_FOR_TA_ALLOC_MENG": MIN_ENROLLMENT_FOR_TA_ALLOC_MENG,
+        "STANDARD_ASSESSMENTS": STANDARD_ASSESSMENTS,
+        "DELIVERY_MODE_ADJUSTMENTS": DELIVERY_MODE_ADJUSTMENTS,
+        "UNDERGRADUATE_COURSE": UNDERGRADUATE_COURSE,
+        "GRADUATE_COURSE": GRADUATE_COURSE,
+        "PROFESSIONAL_DEV_COURSE": PROFESSIONAL_DEV_COURSE,
+        "MENG_COURSE": MENG_COURSE,
+        "SPECIAL_CASES": SPECIAL_CASES,
+        "LAB_ONLY_COURSES": LAB_ONLY_COURSES,
+        "LAB_PROFILES": LAB_PROFILES,
+    })
+}
+
+pub fn sha256_file(path: &str) -> String {
+    let bytes = fs::read(path).unwrap_or_else(|e| panic!("Can't read {}: {}", path, e));
+    sha256(&bytes)
+}
+
+pub fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn write_manifest(path: &str, manifest: &Manifest) {
+    let text = serde_json::to_string_pretty(manifest).unwrap();
+    fs::write(path, text + "\n").unwrap_or_else(|e| panic!("Can't write {}: {}", path, e));
+    println!("Wrote run manifest to {}", path);
+}
+
+pub fn read_manifest(path: &str) -> Manifest {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("Can't read {}: {}", path, e));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("Bad manifest {}: {}", path, e))
+}
+
+// Names of the settings that are different (or only in one of the two).
+pub fn configuration_differences(recorded: &Value, current: &Value) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let recorded = recorded.as_object().unwrap_or(&empty);
+    let current = current.as_object().unwrap_or(&empty);
+    let mut names: Vec<&String> = recorded.keys().chain(current.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| recorded.get(*name) != current.get(*name))
+        .cloned()
+        .collect()
+}
+
+// UTC, like 2026-10-19T14:05:09Z
+pub fn timestamp_now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    format_timestamp(seconds)
+}
+
+fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    // Days since 1970-01-01 to a calendar date (Howard Hinnant's civil_from_days)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::{
+        configuration_differences, effective_configuration, format_timestamp, sha256_file,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn timestamps_are_utc_dates() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_792_418_709), "2026-10-19T14:05:09Z");
+    }
+
+    #[test]
+    fn sha256_of_input_file() {
+        let hash = sha256_file("test_files/simple.csv");
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, sha256_file("test_files/simple.csv"));
+        assert_ne!(hash, sha256_file("test_files/two_courses.csv"));
+    }
+
+    #[test]
+    fn configuration_includes_rule_tables_and_special_cases() {
+        let configuration = effective_configuration();
+
+        assert_eq!(configuration["FULL_TA_HOURS"], json!(130.0));
+        assert_eq!(
+            configuration["UNDERGRADUATE_COURSE"][0]["name"],
+            "Midterm Marking"
+        );
+        assert_eq!(
+            configuration["UNDERGRADUATE_COURSE"][0]["hours"],
+            json!(0.2)
+        );
+        assert_eq!(configuration["SPECIAL_CASES"][0]["course"], "ECE498A");
+        assert_eq!(configuration["LAB_ONLY_COURSES"][0], "NE340L");
+    }
+
+    #[test]
+    fn configuration_differences_name_the_changed_settings() {
+        let recorded = json!({"FULL_TA_HOURS": 130.0, "MIN_TA_THRESHOLD": 0.25, "OLD": 1});
+        let current = json!({"FULL_TA_HOURS": 120.0, "MIN_TA_THRESHOLD": 0.25, "NEW": 1});
+
+        assert_eq!(
+            configuration_differences(&recorded, &current),
+            vec!["FULL_TA_HOURS", "NEW", "OLD"]
+        );
+        assert!(configuration_differences(&current, &current).is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::configuration::{
+    DELIVERY_MODE_ADJUSTMENTS, FIRST_YEAR_UPLIFT, FULL_TA_HOURS, GRADUATE_COURSE,
+    LAB_INSTRUCTOR_ADJUSTMENT, LAB_RATIO_DENOMINATOR, MENG_COURSE,
+    MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD, MIN_ENROLLMENT_FOR_TA_ALLOC_MENG,
+    MIN_ENROLLMENT_FOR_TA_ALLOC_PD, MIN_ENROLLMENT_FOR_TA_ALLOC_UG, MIN_TA_THRESHOLD,
+    PROFESSIONAL_DEV_COURSE, ROUNDING_POLICY, STANDARD_ASSESSMENTS, UNDERGRADUATE_COURSE,
+    UNIT_WEIGHT_BASELINE,
+};
+use crate::fixed::Fixed;
+use crate::specialcases::{LAB_ONLY_COURSES, LAB_PROFILES, SPECIAL_CASES};
+use crate::types::{
+    AllocationRule, AllocationType, Applicability, Assessment, CalculationRule, RoundingMode,
+    RoundingScope, TAHourAllocation, UpliftFormula,
+};
+use std::fmt::Write;
+
+// The allocation policy as a Markdown document, straight from the configuration, so the
+// published version can't drift from the numbers the program produces.
+pub fn render_policy() -> String {
+    let mut doc = String::new();
+    writeln!(doc, "# TA Allocation Policy").unwrap();
+    writeln!(doc).unwrap();
+    writeln!(
+        doc,
+        "Generated by ta-calculations {} from the configuration it was built with.",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    writeln!(doc).unwrap();
+
+    writeln!(doc, "## General").unwrap();
+    writeln!(doc).unwrap();
+    writeln!(doc, "* One full TA is {} hours.", FULL_TA_HOURS).unwrap();
+    writeln!(doc, "* {}", describe_rounding()).unwrap();
+    writeln!(
+        doc,
+        "* An allocation below {} TA is not issued.",
+        MIN_TA_THRESHOLD
+    )
+    .unwrap();
+    writeln!(
+        doc,
+        "* Minimum enrollment for any TA support: {} (undergraduate), {} (graduate), {} (professional development), {} (MEng).",
+        MIN_ENROLLMENT_FOR_TA_ALLOC_UG,
+        MIN_ENROLLMENT_FOR_TA_ALLOC_GRAD,
+        MIN_ENROLLMENT_FOR_TA_ALLOC_PD,
+        MIN_ENROLLMENT_FOR_TA_ALLOC_MENG
+    )
+    .unwrap();
+    writeln!(
+        doc,
+        "* Labs are staffed at one TA per {} students in a section, less {} for the lab instructor.",
+        LAB_RATIO_DENOMINATOR, LAB_INSTRUCTOR_ADJUSTMENT
+    )
+    .unwrap();
+    writeln!(
+        doc,
+        "* Marking hours assume {} midterm(s), {} and {} assignment(s), and are scaled to the course's actual assessments.",
+        STANDARD_ASSESSMENTS.midterms,
+        if STANDARD_ASSESSMENTS.final_exam { "a final exam" } else { "no final exam" },
+        STANDARD_ASSESSMENTS.assignments
+    )
+    .unwrap();
+    writeln!(
+        doc,
+        "* Items marked as scaling with unit weight are for a {} unit course.",
+        UNIT_WEIGHT_BASELINE
+    )
+    .unwrap();
+    writeln!(doc).unwrap();
+
+    let tables: [(&str, &[TAHourAllocation]); 4] = [
+        (
+            "Undergraduate courses (including first year)",
+            UNDERGRADUATE_COURSE,
+        ),
+        ("Graduate courses", GRADUATE_COURSE),
+        ("Professional development courses", PROFESSIONAL_DEV_COURSE),
+        ("MEng courses", MENG_COURSE),
+    ];
+    for (title, table) in tables {
+        writeln!(doc, "## {}", title).unwrap();
+        writeln!(doc).unwrap();
+        writeln!(
+            doc,
+            "| Item | Hours | Charged | Applies to | Counts toward | Notes |"
+        )
+        .unwrap();
+        writeln!(doc, "|---|---|---|---|---|---|").unwrap();
+        for allocation in table {
+            writeln!(
+                doc,
+                "| {} | {} | {} | {} | {} | {} |",
+                allocation.name,
+                allocation.hours,
+                describe_calc_rule(allocation.calc_rule),
+                describe_applicability(allocation.applies_to),
+                describe_alloc_type(allocation.alloc_type),
+                describe_notes(allocation)
+            )
+            .unwrap();
+        }
+        writeln!(doc).unwrap();
+    }
+
+    let uplift = FIRST_YEAR_UPLIFT;
+    writeln!(doc, "## {}", uplift.name).unwrap();
+    writeln!(doc).unwrap();
+    writeln!(
+        doc,
+        "{} for {} with a unit weight of at least {}, scaled by unit weight against {}. Counts toward {}.",
+        match uplift.formula {
+            UpliftFormula::PER_STUDENT(hours) => format!("{} hours per student", hours),
+            UpliftFormula::PER_SECTION(hours) => format!("{} hours per lecture section", hours),
+            UpliftFormula::PERCENTAGE(percent) =>
+                format!("{}% of the hours from the rule table", percent),
+        },
+        describe_applicability(uplift.applies_to),
+        uplift.min_unit_weight,
+        UNIT_WEIGHT_BASELINE,
+        describe_alloc_type(uplift.alloc_type)
+    )
+    .unwrap();
+    writeln!(doc).unwrap();
+
+    writeln!(doc, "## Online and hybrid courses").unwrap();
+    writeln!(doc).unwrap();
+    writeln!(doc, "| Delivery | Item | Change | Reason |").unwrap();
+    writeln!(doc, "|---|---|---|---|").unwrap();
+    for adjustment in DELIVERY_MODE_ADJUSTMENTS {
+        writeln!(
+            doc,
+            "| {:?} | {} | {} | {} |",
+            adjustment.mode,
+            adjustment.rule,
+            if adjustment.factor == Fixed::ZERO {
+                String::from("not charged")
+            } else {
+                format!("× {}", adjustment.factor)
+            },
+            adjustment.reason
+        )
+        .unwrap();
+    }
+    writeln!(doc).unwrap();
+
+    writeln!(doc, "## Special cases").unwrap();
+    writeln!(doc).unwrap();
+    writeln!(doc, "| Course | Allocation | Reason |").unwrap();
+    writeln!(doc, "|---|---|---|").unwrap();
+    for sc in SPECIAL_CASES {
+        writeln!(
+            doc,
+            "| {} | {} | {} |",
+            sc.course,
+            describe_special_case(&sc.allocation_rule, sc.allocation_amount),
+            sc.reason
+        )
+        .unwrap();
+    }
+    writeln!(doc).unwrap();
+
+    writeln!(doc, "## Lab-only courses").unwrap();
+    writeln!(doc).unwrap();
+    writeln!(
+        doc,
+        "These courses only get the lab items, as does any course whose code ends in L: {}.",
+        LAB_ONLY_COURSES.join(", ")
+    )
+    .unwrap();
+    writeln!(doc).unwrap();
+
+    writeln!(doc, "## Lab staffing exceptions").unwrap();
+    writeln!(doc).unwrap();
+    writeln!(
+        doc,
+        "| Course | Students per TA | Lab instructor | Reason |"
+    )
+    .unwrap();
+    writeln!(doc, "|---|---|---|---|").unwrap();
+    for profile in LAB_PROFILES {
+        writeln!(
+            doc,
+            "| {} | {} | {} | {} |",
+            profile.course,
+            profile.students_per_ta,
+            if profile.lab_instructor { "yes" } else { "no" },
+            profile.reason
+        )
+        .unwrap();
+    }
+    doc
+}
+
+fn describe_rounding() -> String {
+    let mode = match ROUNDING_POLICY.mode {
+        RoundingMode::NEAREST_QUARTER => "rounded to the nearest quarter TA",
+        RoundingMode::UP_TO_QUARTER => "rounded up to the next quarter TA",
+        RoundingMode::NEAREST_TENTH => "rounded to the nearest tenth of a TA",
+        RoundingMode::NO_ROUNDING => "not rounded",
+    };
+    let scope = match ROUNDING_POLICY.scope {
+        RoundingScope::TOTAL => "the total and the lab share are each",
+        RoundingScope::PER_COMPONENT => "the lecture and lab shares are each",
+    };
+    format!("Allocations: {} {}.", scope, mode)
+}
+
+fn describe_calc_rule(rule: CalculationRule) -> &'static str {
+    match rule {
+        CalculationRule::PER_TERM => "per term",
+        CalculationRule::PER_STUDENT => "per student",
+        CalculationRule::PER_LAB => "per lab TA per lab section",
+        CalculationRule::PER_LEC_SECTION => "per lecture section",
+        CalculationRule::PER_TUT_SECTION => "per tutorial section",
+    }
+}
+
+fn describe_applicability(applies_to: Applicability) -> String {
+    match applies_to {
+        Applicability::ALWAYS => String::from("all courses"),
+        Applicability::WITH_LABS => String::from("courses with labs"),
+        Applicability::WITHOUT_LABS => String::from("courses without labs"),
+        Applicability::LAB_ONLY => String::from("lab-only courses"),
+        Applicability::NOT_LAB_ONLY => String::from("courses that aren't lab-only"),
+        Applicability::COURSE_TYPES(types) => {
+            let names: Vec<String> = types.iter().map(|t| format!("{:?}", t)).collect();
+            format!("{} courses", names.join(", "))
+        }
+    }
+}
+
+fn describe_alloc_type(alloc_type: AllocationType) -> &'static str {
+    match alloc_type {
+        AllocationType::LAB => "lab",
+        AllocationType::LECTURE => "lecture",
+    }
+}
+
+fn describe_notes(allocation: &TAHourAllocation) -> String {
+    let mut notes: Vec<&str> = Vec::new();
+    match allocation.assessment {
+        Some(Assessment::MIDTERM) => notes.push("per midterm"),
+        Some(Assessment::FINAL) => notes.push("only with a final exam"),
+        Some(Assessment::ASSIGNMENT) => notes.push("scaled by number of assignments"),
+        None => {}
+    }
+    if allocation.scale_by_unit_weight {
+        notes.push("scales with unit weight");
+    }
+    notes.join("; ")
+}
+
+fn describe_special_case(rule: &AllocationRule, amount: Fixed) -> String {
+    match rule {
+        AllocationRule::NO_TA_ALLOC => String::from("no TA"),
+        AllocationRule::MIN_ALLOC => format!("at least {} TA", amount),
+        AllocationRule::MAX_ALLOC => format!("at most {} TA", amount),
+        AllocationRule::PER_SECTION => format!("{} TA per lecture section", amount),
+        AllocationRule::PER_LAB_SECTION => format!("{} TA per lab section", amount),
+        AllocationRule::FIXED => format!("{} TA", amount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::policy::render_policy;
+
+    #[test]
+    fn policy_uses_the_live_rule_tables() {
+        let policy = render_policy();
+
+        assert!(policy.starts_with("# TA Allocation Policy\n"));
+        assert!(policy.contains("* One full TA is 130 hours."));
+        assert!(policy.contains(
+            "| Tutorials | 11 | per tutorial section | courses that aren't lab-only | lecture |  |"
+        ));
+        assert!(policy.contains(
+            "| Assignment Marking | 1 | per student | courses without labs | lecture | scaled by number of assignments |"
+        ));
+    }
+
+    #[test]
+    fn policy_lists_special_cases_and_lab_only_courses() {
+        let policy = render_policy();
+
+        assert!(policy.contains("| ECE498A | no TA | Capstone Course |"));
+        assert!(policy.contains("NE340L, NE455A, ECE198, ECE298."));
+        assert!(policy.contains("| ECE260 | 15 | no | High power lab |"));
+    }
+}
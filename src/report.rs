@@ -0,0 +1,147 @@
+use crate::fixed::Fixed;
+use crate::types::{Course, CourseAllocation};
+
+pub struct InstructorSummary {
+    pub instructor: String,
+    // (course name, the instructor's share of its allocation, number of instructors)
+    pub courses: Vec<(String, Fixed, usize)>,
+    pub total: Fixed,
+}
+
+// Co-taught courses list everyone in one cell: "First Instructor; Second Instructor"
+// or "First Instructor / Second Instructor".
+pub fn split_instructors(instructor: &str) -> Vec<String> {
+    instructor
+        .split([';', '/'])
+        .map(str::trim)
+        .filter(|i| !i.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// A co-taught course's allocation is split evenly between its instructors, so that adding
+// up everyone's total gives the same number as adding up the courses. When it doesn't
+// divide evenly to four places, the first instructor's share takes the difference.
+pub fn summarize_by_instructor(
+    allocations: &[(Course, CourseAllocation)],
+) -> Vec<InstructorSummary> {
+    let mut summaries: Vec<InstructorSummary> = Vec::new();
+    for (c, allocation) in allocations {
+        let mut instructors = split_instructors(&c.instructor);
+        if instructors.is_empty() {
+            instructors.push(String::from("(no instructor)"));
+        }
+        let share = allocation.total / Fixed::from_int(instructors.len() as i32);
+        // Whatever's lost rounding the shares goes to the first instructor
+        let remainder = allocation.total - share * instructors.len() as i32;
+        for (n, instructor) in instructors.iter().enumerate() {
+            let share = if n == 0 { share + remainder } else { share };
+            let index = match summaries.iter().position(|s| s.instructor == *instructor) {
+                Some(i) => i,
+                None => {
+                    summaries.push(InstructorSummary {
+                        instructor: instructor.clone(),
+                        courses: Vec::new(),
+                        total: Fixed::ZERO,
+                    });
+                    summaries.len() - 1
+                }
+            };
+            let summary = &mut summaries[index];
+            summary
+                .courses
+                .push((c.name.clone(), share, instructors.len()));
+            summary.total += share;
+        }
+    }
+    summaries.sort_by(|a, b| a.instructor.cmp(&b.instructor));
+    summaries
+}
+
+pub fn write_instructor_report(path: &str, summaries: &[InstructorSummary]) {
+    let mut wtr = csv::Writer::from_path(path).unwrap();
+    wtr.write_record([
+        "Instructor",
+        "Courses",
+        "Number of Courses",
+        "Total TA Allocation",
+    ])
+    .unwrap();
+    for s in summaries {
+        let courses: Vec<String> = s
+            .courses
+            .iter()
+            .map(|(name, share, instructors)| {
+                if *instructors > 1 {
+                    format!("{} ({}, split {} ways)", name, share, instructors)
+                } else {
+                    format!("{} ({})", name, share)
+                }
+            })
+            .collect();
+        wtr.write_record(&[
+            s.instructor.clone(),
+            courses.join("; "),
+            s.courses.len().to_string(),
+            s.total.to_string(),
+        ])
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed::Fixed;
+    use crate::report::{split_instructors, summarize_by_instructor};
+    use crate::types::calculated;
+
+    #[test]
+    fn split_instructors_on_semicolon_or_slash() {
+        assert_eq!(split_instructors("Ann Smith"), vec!["Ann Smith"]);
+        assert_eq!(
+            split_instructors("Ann Smith; Bo Chen"),
+            vec!["Ann Smith", "Bo Chen"]
+        );
+        assert_eq!(
+            split_instructors("Ann Smith / Bo Chen/Cy Doe"),
+            vec!["Ann Smith", "Bo Chen", "Cy Doe"]
+        );
+        assert!(split_instructors("  ").is_empty());
+    }
+
+    #[test]
+    fn allocations_are_grouped_by_instructor() {
+        let mut allocations = vec![
+            calculated("ECE 150", "Ann Smith; Bo Chen"),
+            calculated("ECE 250", "Bo Chen"),
+            calculated("ECE 350", "Ann Smith"),
+            calculated("ECE 498A", ""),
+        ];
+        for ((_, allocation), total) in allocations.iter_mut().zip([7.5, 2.0, 0.5, 0.0]) {
+            allocation.total = Fixed::new(total);
+        }
+
+        let summaries = summarize_by_instructor(&allocations);
+
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].instructor, "(no instructor)");
+        assert_eq!(summaries[1].instructor, "Ann Smith");
+        assert_eq!(summaries[1].courses.len(), 2);
+        assert_eq!(summaries[1].total, Fixed::new(4.25));
+        assert_eq!(summaries[2].instructor, "Bo Chen");
+        assert_eq!(summaries[2].total, Fixed::new(5.75));
+    }
+
+    #[test]
+    fn three_way_split_adds_up_to_the_course() {
+        let mut allocations = vec![calculated("ECE 150", "Ann Smith; Bo Chen; Cy Doe")];
+        allocations[0].1.total = Fixed::new(1.0);
+
+        let summaries = summarize_by_instructor(&allocations);
+
+        let shares: Vec<Fixed> = summaries.iter().map(|s| s.courses[0].1).collect();
+        assert_eq!(shares.iter().copied().sum::<Fixed>(), Fixed::new(1.0));
+        assert_eq!(shares[0], Fixed::new(0.3334));
+        assert_eq!(shares[1], Fixed::new(0.3333));
+    }
+}
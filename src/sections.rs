@@ -0,0 +1,236 @@
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use crate::input::find_column;
+use crate::types::Course;
+
+// The registrar export has one row per section instead of one per course:
+// Course,Section,Instructor,Enrollment,Unit Weight
+// where Section is something like "LEC 001", "LAB 101" or "TUT 201".
+// Rows for the same course (by normalized code) are rolled up into one Course.
+pub fn read_sections_file(path: &String) -> Vec<Course> {
+    let mut rdr = csv::Reader::from_path(path).unwrap();
+    let headers = rdr.headers().unwrap().clone();
+    let column = |name| {
+        find_column(&headers, name)
+            .unwrap_or_else(|| panic!("No {} column in section file {}", name, path))
+    };
+    let course_column = column("Course");
+    let section_column = column("Section");
+    let instructor_column = column("Instructor");
+    let enrollment_column = column("Enrollment");
+    let unit_weight_column = find_column(&headers, "Unit Weight");
+
+    let mut courses: Vec<(CourseCode, SectionTotals)> = Vec::new();
+    for (row, result) in rdr.records().enumerate() {
+        let record = result.unwrap();
+        let field = |i: usize| record.get(i).unwrap_or("").trim();
+        // Row 1 is the header
+        let location = || format!("row {} of {}", row + 2, path);
+
+        let name = field(course_column);
+        let code = CourseCode::parse(name).unwrap_or_else(|e| panic!("{} ({})", e, location()));
+        let section = field(section_column);
+        let enrollment: i32 = field(enrollment_column).parse().unwrap_or_else(|_| {
+            panic!(
+                "Expected a whole number for enrollment, not '{}' ({})",
+                field(enrollment_column),
+                location()
+            )
+        });
+        let unit_weight = unit_weight_column
+            .map(field)
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                v.parse::<Fixed>()
+                    .unwrap_or_else(|e| panic!("{} ({})", e, location()))
+            });
+
+        let index = match courses.iter().position(|(c, _)| *c == code) {
+            Some(i) => i,
+            None => {
+                courses.push((code, SectionTotals::new(name)));
+                courses.len() - 1
+            }
+        };
+        let totals = &mut courses[index].1;
+        let instructor = field(instructor_column);
+        if totals.unit_weight.is_none() {
+            totals.unit_weight = unit_weight;
+        }
+        match section_kind(section).as_str() {
+            "LEC" => {
+                totals.lec_sections += 1;
+                totals.lec_enrollment += enrollment;
+                add_instructor(&mut totals.lec_instructors, instructor);
+            }
+            "LAB" => {
+                totals.lab_sections += 1;
+                totals.lab_enrollment += enrollment;
+                add_instructor(&mut totals.other_instructors, instructor);
+            }
+            "TUT" => {
+                totals.tut_sections += 1;
+                add_instructor(&mut totals.other_instructors, instructor);
+            }
+            _ => println!(
+                "Ignoring section '{}' of {} since it's not LEC, LAB or TUT ({})",
+                section,
+                name,
+                location()
+            ),
+        }
+    }
+
+    courses
+        .into_iter()
+        .map(|(_, totals)| totals.into_course())
+        .collect()
+}
+
+fn add_instructor(instructors: &mut Vec<String>, instructor: &str) {
+    if !instructor.is_empty() && !instructors.iter().any(|i| i == instructor) {
+        instructors.push(instructor.to_string());
+    }
+}
+
+struct SectionTotals {
+    name: String,
+    // The LEC instructors teach the course; the LAB and TUT ones only count when there's no LEC
+    lec_instructors: Vec<String>,
+    other_instructors: Vec<String>,
+    lec_sections: i32,
+    lab_sections: i32,
+    tut_sections: i32,
+    lec_enrollment: i32,
+    lab_enrollment: i32,
+    unit_weight: Option<Fixed>,
+}
+
+impl SectionTotals {
+    fn new(name: &str) -> SectionTotals {
+        SectionTotals {
+            name: name.to_string(),
+            lec_instructors: Vec::new(),
+            other_instructors: Vec::new(),
+            lec_sections: 0,
+            lab_sections: 0,
+            tut_sections: 0,
+            lec_enrollment: 0,
+            lab_enrollment: 0,
+            unit_weight: None,
+        }
+    }
+
+    // Every student is in a LEC section and usually a LAB too, so only LEC enrollment is
+    // counted. A lab-only course has no LEC sections, so its LAB sections are counted instead.
+    fn into_course(self) -> Course {
+        let enrollment = if self.lec_sections > 0 {
+            self.lec_enrollment
+        } else {
+            self.lab_enrollment
+        };
+        let instructors = if self.lec_instructors.is_empty() {
+            self.other_instructors
+        } else {
+            self.lec_instructors
+        };
+        Course {
+            name: self.name,
+            instructor: instructors.join("; "),
+            enrollment,
+            lec_sections: self.lec_sections,
+            lab_sections: self.lab_sections,
+            unit_weight: self.unit_weight.unwrap_or(Fixed::new(0.5)),
+            // No TUT rows means the export doesn't say, not that there are none, so it gets
+            // the same default as a CSV without the Tutorial Sections column.
+            tut_sections: if self.tut_sections > 0 {
+                Some(self.tut_sections)
+            } else {
+                None
+            },
+            ..Default::default()
+        }
+    }
+}
+
+// "LEC 001" -> "LEC", "lab101" -> "LAB"
+fn section_kind(section: &str) -> String {
+    section
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed::Fixed;
+    use crate::sections::{read_sections_file, section_kind};
+
+    #[test]
+    fn section_kind_handles_spacing_and_case() {
+        assert_eq!(section_kind("LEC 001"), "LEC");
+        assert_eq!(section_kind("lab101"), "LAB");
+        assert_eq!(section_kind("TUT"), "TUT");
+    }
+
+    #[test]
+    fn sections_are_grouped_by_course_code() {
+        let courses = read_sections_file(&String::from("test_files/sections.csv"));
+
+        assert_eq!(courses.len(), 3);
+        let ece150 = courses.first().unwrap();
+        assert_eq!(ece150.name, "ECE 150");
+        assert_eq!(ece150.lec_sections, 2);
+        assert_eq!(ece150.lab_sections, 3);
+        assert_eq!(ece150.tut_sections, Some(4));
+        assert_eq!(ece150.unit_weight, Fixed::new(0.5));
+    }
+
+    #[test]
+    fn enrollment_only_counts_lec_sections() {
+        let courses = read_sections_file(&String::from("test_files/sections.csv"));
+        let ece150 = courses.first().unwrap();
+
+        // 230 + 220 in LEC; the LAB and TUT rows have the same students again
+        assert_eq!(ece150.enrollment, 450);
+    }
+
+    #[test]
+    fn instructors_of_all_lec_sections_are_kept() {
+        let courses = read_sections_file(&String::from("test_files/sections.csv"));
+
+        assert_eq!(
+            courses.first().unwrap().instructor,
+            "First Instructor; Second Instructor"
+        );
+        assert_eq!(courses.get(1).unwrap().instructor, "Third Instructor");
+    }
+
+    #[test]
+    fn lab_only_course_counts_lab_enrollment() {
+        let courses = read_sections_file(&String::from("test_files/sections.csv"));
+        let ne216l = courses.get(2).unwrap();
+
+        assert_eq!(ne216l.lec_sections, 0);
+        assert_eq!(ne216l.lab_sections, 2);
+        assert_eq!(ne216l.enrollment, 60);
+        assert_eq!(ne216l.unit_weight, Fixed::new(0.25));
+    }
+
+    #[test]
+    fn course_without_tut_rows_gets_the_default_tutorials() {
+        let courses = read_sections_file(&String::from("test_files/sections.csv"));
+        let ece252 = courses.get(1).unwrap();
+
+        assert_eq!(ece252.tut_sections, None);
+        assert_eq!(ece252.tutorial_sections(), ece252.lec_sections);
+    }
+
+    #[test]
+    fn lab_only_course_takes_lab_instructors() {
+        let courses = read_sections_file(&String::from("test_files/sections.csv"));
+
+        assert_eq!(courses.get(2).unwrap().instructor, "Lab Coordinator");
+    }
+}
@@ -0,0 +1,242 @@
+use crate::calculator::{calculate_ta_hours, check_for_special_case};
+use crate::coursecode::CourseCode;
+use crate::json::check_fields;
+use crate::types::{Course, CourseAllocation};
+use crate::validation::check_course;
+use serde_json::{json, Value};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use tiny_http::{Header, Response, Server};
+
+// Answers two kinds of request, both in JSON:
+//   POST /calculate        a Course in the body, its allocation back
+//   GET  /courses/{code}   the allocation for a course in the term file the server was started with
+pub fn serve(address: &str, allocations: &[(Course, CourseAllocation)]) {
+    let server =
+        Server::http(address).unwrap_or_else(|e| panic!("Can't listen on {}: {}", address, e));
+    println!(
+        "Serving {} courses on http://{} (Ctrl-C to stop)",
+        allocations.len(),
+        address
+    );
+    handle_requests(&server, allocations);
+}
+
+fn handle_requests(server: &Server, allocations: &[(Course, CourseAllocation)]) {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, json) = match request.as_reader().read_to_string(&mut body) {
+            // A request that trips up the calculation gets a 500, not the end of the server.
+            Ok(_) => catch_unwind(AssertUnwindSafe(|| {
+                route(request.method().as_str(), request.url(), &body, allocations)
+            }))
+            .unwrap_or_else(|_| (500, error(String::from("The calculation failed.")))),
+            Err(e) => (400, error(format!("Can't read the request: {}", e))),
+        };
+        println!("{} {} -> {}", request.method(), request.url(), status);
+        let response = Response::from_string(json.to_string())
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+        if let Err(e) = request.respond(response) {
+            println!("Couldn't send the response: {}", e);
+        }
+    }
+}
+
+pub fn route(
+    method: &str,
+    url: &str,
+    body: &str,
+    allocations: &[(Course, CourseAllocation)],
+) -> (u16, Value) {
+    // Nothing takes a query string, so it's ignored rather than made part of the path.
+    let path = url.split('?').next().unwrap_or_default();
+    match (method, path) {
+        ("POST", "/calculate") => calculate_posted(body),
+        ("GET", _) if path.starts_with("/courses/") => {
+            match percent_decode(&path["/courses/".len()..]) {
+                Ok(code) => look_up(&code, allocations),
+                Err(e) => (400, error(e)),
+            }
+        }
+        _ => (
+            404,
+            error(format!("There's nothing at {} {}.", method, url)),
+        ),
+    }
+}
+
+fn calculate_posted(body: &str) -> (u16, Value) {
+    let course: Course = match serde_json::from_str(body) {
+        Ok(c) => c,
+        Err(e) => return (400, error(format!("Can't read the course: {}", e))),
+    };
+    // This parses the course code, which the calculation takes from the name with
+    // Course::code(), so a bad one has to be turned away here rather than panic later.
+    if let Err(e) = check_fields(&course) {
+        return (400, error(e));
+    }
+    // Unlike a term file, there's nobody to read warnings here, so anything implausible
+    // is turned away.
+    let problems = check_course(&course);
+    if !problems.is_empty() {
+        return (400, error(problems.join(" ")));
+    }
+    let allocation = check_for_special_case(&course, calculate_ta_hours(&course));
+    (200, json!({ "course": course, "allocation": allocation }))
+}
+
+// A course with more than one row in the term file has more than one allocation, so this
+// always answers with a list.
+fn look_up(code: &str, allocations: &[(Course, CourseAllocation)]) -> (u16, Value) {
+    let code = match CourseCode::parse(code) {
+        Ok(code) => code,
+        Err(e) => return (400, error(e.to_string())),
+    };
+    let found: Vec<Value> = allocations
+        .iter()
+        .filter(|(c, _)| c.code() == code)
+        .map(|(c, allocation)| json!({ "course": c, "allocation": allocation }))
+        .collect();
+    if found.is_empty() {
+        (404, error(format!("Course {} isn't in this term.", code)))
+    } else {
+        (200, Value::Array(found))
+    }
+}
+
+// %XX escapes in a URL path, decoded as UTF-8.
+fn percent_decode(text: &str) -> Result<String, String> {
+    let bad = || format!("Bad escape in {}", text);
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut rest = text.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = tail.get(..2).ok_or_else(bad)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| bad())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| bad())?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| bad())
+}
+
+fn error(message: String) -> Value {
+    json!({ "error": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::{handle_requests, route};
+    use crate::types::{calculated, Course, CourseAllocation};
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    const ECE150: &str = r#"{"name": "ECE 150", "instructor": "Bob Example", "enrollment": 450,
+        "lec_sections": 2, "lab_sections": 1, "unit_weight": 1.0}"#;
+
+    fn term() -> Vec<(Course, CourseAllocation)> {
+        vec![calculated("ECE 498A", "Ann Smith")]
+    }
+
+    #[test]
+    fn post_calculate_returns_the_allocation_and_breakdown() {
+        let (status, json) = route("POST", "/calculate", ECE150, &[]);
+
+        assert_eq!(status, 200);
+        assert_eq!(json["allocation"]["total"], 7.75);
+        assert_eq!(json["allocation"]["lab_amount"], 4.5);
+        assert_eq!(
+            json["allocation"]["components"][0]["name"],
+            "Midterm Marking"
+        );
+        assert_eq!(json["course"]["delivery_mode"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn post_calculate_rejects_bad_input() {
+        assert_eq!(route("POST", "/calculate", "{", &[]).0, 400);
+        let (status, json) = route("POST", "/calculate", r#"{"name": "150"}"#, &[]);
+        assert_eq!(status, 400);
+        assert!(json["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid course code '150'"));
+        let too_many_students = r#"{"name": "ECE 150", "enrollment": 4500, "lec_sections": 1,
+            "unit_weight": 0.5}"#;
+        assert_eq!(route("POST", "/calculate", too_many_students, &[]).0, 400);
+    }
+
+    #[test]
+    fn post_calculate_rejects_zero_lab_ratio() {
+        let body = r#"{"name": "ECE 150", "enrollment": 100, "lec_sections": 1,
+            "lab_sections": 2, "lab_students_per_ta": 0, "unit_weight": 0.5}"#;
+
+        let (status, json) = route("POST", "/calculate", body, &[]);
+
+        assert_eq!(status, 400);
+        assert_eq!(
+            json["error"],
+            "lab_students_per_ta must be more than 0, not 0"
+        );
+    }
+
+    #[test]
+    fn get_course_looks_up_the_term() {
+        let term = term();
+
+        let (status, json) = route("GET", "/courses/ece498a", "", &term);
+        assert_eq!(status, 200);
+        assert_eq!(json[0]["course"]["instructor"], "Ann Smith");
+        assert_eq!(json[0]["allocation"]["total"], 0.0);
+        assert_eq!(
+            json[0]["allocation"]["special_case_reason"],
+            "Capstone Course"
+        );
+
+        assert_eq!(route("GET", "/courses/ECE%20498A", "", &term).0, 200);
+        assert_eq!(route("GET", "/courses/ECE%2D498A", "", &term).0, 200);
+        assert_eq!(route("GET", "/courses/ECE498A?x=1", "", &term).0, 200);
+        assert_eq!(route("GET", "/courses/ECE%2", "", &term).0, 400);
+        assert_eq!(route("GET", "/courses/ECE%FF498A", "", &term).0, 400);
+        assert_eq!(route("GET", "/courses/ECE150", "", &term).0, 404);
+        assert_eq!(route("GET", "/nowhere", "", &term).0, 404);
+    }
+
+    #[test]
+    fn course_from_get_can_be_posted_back() {
+        let term = term();
+        let (_, found) = route("GET", "/courses/ECE498A", "", &term);
+        let mut course = found[0]["course"].clone();
+        course["enrollment"] = serde_json::Value::from(180);
+
+        let (status, json) = route("POST", "/calculate", &course.to_string(), &term);
+
+        assert_eq!(status, 200);
+        assert_eq!(json["course"]["enrollment"], 180);
+    }
+
+    #[test]
+    fn serves_requests_on_localhost() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap();
+        std::thread::spawn(move || handle_requests(&server, &term()));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /calculate HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            ECE150.len(),
+            ECE150
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("application/json"));
+        assert!(response.contains("\"total\":7.75"));
+    }
+}
@@ -1,107 +1,108 @@
-use crate::types::{AllocationRule, SpecialCase};
+use crate::fixed::Fixed;
+use crate::types::{AllocationRule, LabProfile, SpecialCase};
 
 pub const SPECIAL_CASES: &[SpecialCase] = &[
     SpecialCase {
         course: "ECE498A",
         reason: "Capstone Course",
         allocation_rule: AllocationRule::NO_TA_ALLOC,
-        allocation_amount: 0.0,
+        allocation_amount: Fixed::new(0.0),
     },
     SpecialCase {
         course: "ECE498B",
         reason: "Capstone Course",
         allocation_rule: AllocationRule::NO_TA_ALLOC,
-        allocation_amount: 0.0,
+        allocation_amount: Fixed::new(0.0),
     },
     SpecialCase {
         course: "MTE482",
         reason: "Capstone Course",
         allocation_rule: AllocationRule::NO_TA_ALLOC,
-        allocation_amount: 0.0,
+        allocation_amount: Fixed::new(0.0),
     },
     SpecialCase {
         course: "NE340L",
         reason: "Cleanroom Lab Course",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 2.5,
+        allocation_amount: Fixed::new(2.5),
     },
     SpecialCase {
         course: "NE343",
         reason: "Lab Course",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 2.5,
+        allocation_amount: Fixed::new(2.5),
     },
     SpecialCase {
         course: "ECE459",
         reason: "Project Course",
         allocation_rule: AllocationRule::MAX_ALLOC,
-        allocation_amount: 6.0,
+        allocation_amount: Fixed::new(6.0),
     },
     SpecialCase {
         course: "NE455B",
         reason: "Cleanroom Lab Course",
         allocation_rule: AllocationRule::MIN_ALLOC,
-        allocation_amount: 2.5,
+        allocation_amount: Fixed::new(2.5),
     },
     SpecialCase {
         course: "NE409",
         reason: "Half-Credit No TA Course",
         allocation_rule: AllocationRule::NO_TA_ALLOC,
-        allocation_amount: 0.0,
+        allocation_amount: Fixed::new(0.0),
     },
     SpecialCase {
         course: "ECE190",
         reason: "1 TA per Section 1st Year Course",
         allocation_rule: AllocationRule::PER_SECTION,
-        allocation_amount: 1.0,
+        allocation_amount: Fixed::new(1.0),
     },
     SpecialCase {
         course: "ECE298",
         reason: "Hands-On Lab Course",
         allocation_rule: AllocationRule::PER_LAB_SECTION,
-        allocation_amount: 0.75,
+        allocation_amount: Fixed::new(0.75),
     },
     SpecialCase {
         course: "ECE198",
         reason: "Hands-On Lab Course",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 8.0,
+        allocation_amount: Fixed::new(8.0),
     },
     SpecialCase {
         course: "ECE464",
         reason: "High Voltage Lab",
         allocation_rule: AllocationRule::MIN_ALLOC,
-        allocation_amount: 1.0,
+        allocation_amount: Fixed::new(1.0),
     },
     SpecialCase {
         course: "ECE474",
         reason: "Lab Safety",
         allocation_rule: AllocationRule::PER_LAB_SECTION,
-        allocation_amount: 0.4,
+        allocation_amount: Fixed::new(0.4),
     },
     SpecialCase {
         course: "NE216L",
         reason: "Nano Lab",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 1.0,
+        allocation_amount: Fixed::new(1.0),
     },
     SpecialCase {
         course: "NE217L",
         reason: "Nano Lab",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 1.0,
+        allocation_amount: Fixed::new(1.0),
     },
     SpecialCase {
         course: "ECE6607PD",
         reason: "PD Courses",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 1.0,
+        allocation_amount: Fixed::new(1.0),
     },
     SpecialCase {
         course: "ECE6608PD",
         reason: "PD Courses",
         allocation_rule: AllocationRule::FIXED,
-        allocation_amount: 1.0,
+        allocation_amount: Fixed::new(1.0),
     },
 ];
 // 192 (half credit)
@@ -109,8 +110,19 @@ pub const SPECIAL_CASES: &[SpecialCase] = &[
 
 pub const LAB_ONLY_COURSES: &[&str] = &["NE340L", "NE455A", "ECE198", "ECE298"];
 
+// Only for labs with a documented ratio. The NE cleanroom labs stay on their special
+// cases until there's a published safety ratio to put here.
+pub const LAB_PROFILES: &[LabProfile] = &[LabProfile {
+    course: "ECE260",
+    reason: "High power lab",
+    students_per_ta: Fixed::new(15.0),
+    lab_instructor: false,
+}];
+
+#[cfg(test)]
 mod tests {
-    use crate::specialcases::{LAB_ONLY_COURSES, SPECIAL_CASES};
+    use crate::coursecode::CourseCode;
+    use crate::specialcases::{LAB_ONLY_COURSES, LAB_PROFILES, SPECIAL_CASES};
 
     #[test]
     fn no_spaces_in_special_case_course_names() {
@@ -125,4 +137,25 @@ mod tests {
             assert!(!l.contains(" "));
         }
     }
+
+    #[test]
+    fn special_case_course_names_are_normalized_codes() {
+        for sc in SPECIAL_CASES {
+            assert_eq!(CourseCode::parse(sc.course).unwrap().to_string(), sc.course);
+        }
+    }
+
+    #[test]
+    fn lab_only_course_names_are_normalized_codes() {
+        for l in LAB_ONLY_COURSES {
+            assert_eq!(CourseCode::parse(l).unwrap().to_string(), *l);
+        }
+    }
+
+    #[test]
+    fn lab_profile_course_names_are_normalized_codes() {
+        for lp in LAB_PROFILES {
+            assert_eq!(CourseCode::parse(lp.course).unwrap().to_string(), lp.course);
+        }
+    }
 }
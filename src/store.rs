@@ -0,0 +1,499 @@
+use crate::configuration::ROUNDING_POLICY;
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use crate::manifest::{sha256, timestamp_now, Manifest};
+use crate::types::{Course, CourseAllocation};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+// One row per term imported. The configuration it was calculated with is kept once per
+// distinct configuration, since most terms share one. Fixed values are stored as their
+// exact decimal text, so they read back without going through f64.
+const TABLES: &str = "
+CREATE TABLE IF NOT EXISTS configurations (
+    id INTEGER PRIMARY KEY,
+    sha256 TEXT NOT NULL UNIQUE,
+    configuration TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS terms (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    imported_at TEXT NOT NULL,
+    input_file TEXT NOT NULL,
+    input_sha256 TEXT NOT NULL,
+    crate_version TEXT NOT NULL,
+    rounding TEXT NOT NULL,
+    configuration_id INTEGER NOT NULL REFERENCES configurations(id)
+);
+CREATE TABLE IF NOT EXISTS courses (
+    id INTEGER PRIMARY KEY,
+    term_id INTEGER NOT NULL REFERENCES terms(id),
+    position INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    instructor TEXT NOT NULL,
+    enrollment INTEGER NOT NULL,
+    lec_sections INTEGER NOT NULL,
+    lab_sections INTEGER NOT NULL,
+    unit_weight TEXT NOT NULL,
+    multi_instructor INTEGER NOT NULL,
+    details TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS courses_by_code ON courses(code);
+CREATE TABLE IF NOT EXISTS allocations (
+    course_id INTEGER PRIMARY KEY REFERENCES courses(id),
+    total TEXT NOT NULL,
+    lecture_amount TEXT NOT NULL,
+    lab_amount TEXT NOT NULL,
+    special_case TEXT,
+    components TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS overrides (
+    id INTEGER PRIMARY KEY,
+    course_id INTEGER NOT NULL REFERENCES courses(id),
+    allocation TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+";
+
+pub struct HistoryEntry {
+    pub term: String,
+    pub name: String,
+    pub instructor: String,
+    pub enrollment: i32,
+    pub total: Fixed,
+    pub lab_amount: Fixed,
+    pub special_case: Option<String>,
+    // The latest override for the course that term, if any: (allocation, reason)
+    pub override_allocation: Option<(Fixed, String)>,
+}
+
+// Creates the tables the first time, so any path will do for a new database.
+pub fn open_database(path: &str) -> Connection {
+    let conn =
+        Connection::open(path).unwrap_or_else(|e| panic!("Can't open database {}: {}", path, e));
+    conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+    conn.execute_batch(TABLES).unwrap();
+    conn
+}
+
+// The allocations have to be the ones the manifest was written for; the version and
+// configuration stored are the ones it records, not whatever is current.
+pub fn import_term(
+    conn: &mut Connection,
+    term: &str,
+    recorded: &Manifest,
+    allocations: &[(Course, CourseAllocation)],
+) -> Result<(), String> {
+    if term_id(conn, term).is_some() {
+        return Err(format!("Term {} is already in the database.", term));
+    }
+    let configuration = recorded.configuration.to_string();
+    let tx = conn.transaction().unwrap();
+    tx.execute(
+        "INSERT OR IGNORE INTO configurations (sha256, configuration) VALUES (?1, ?2)",
+        params![sha256(configuration.as_bytes()), configuration],
+    )
+    .unwrap();
+    let configuration_id: i64 = tx
+        .query_row(
+            "SELECT id FROM configurations WHERE sha256 = ?1",
+            params![sha256(configuration.as_bytes())],
+            |row| row.get(0),
+        )
+        .unwrap();
+    tx.execute(
+        "INSERT INTO terms (name, imported_at, input_file, input_sha256, crate_version, rounding, configuration_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            term,
+            timestamp_now(),
+            recorded.input_file,
+            recorded.input_sha256,
+            recorded.crate_version,
+            ROUNDING_POLICY.to_string(),
+            configuration_id
+        ],
+    )
+    .unwrap();
+    let term_id = tx.last_insert_rowid();
+
+    for (position, (c, allocation)) in allocations.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO courses (term_id, position, code, name, instructor, enrollment, lec_sections,
+                 lab_sections, unit_weight, multi_instructor, details)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                term_id,
+                position as i64,
+                c.code().to_string(),
+                c.name,
+                c.instructor,
+                c.enrollment,
+                c.lec_sections,
+                c.lab_sections,
+                c.unit_weight.to_string(),
+                c.multi_instructor,
+                serde_json::to_string(c).unwrap()
+            ],
+        )
+        .unwrap();
+        tx.execute(
+            "INSERT INTO allocations (course_id, total, lecture_amount, lab_amount, special_case, components)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                tx.last_insert_rowid(),
+                allocation.total.to_string(),
+                (allocation.total - allocation.lab_amount).to_string(),
+                allocation.lab_amount.to_string(),
+                allocation.special_case_reason,
+                serde_json::to_string(&allocation.components).unwrap()
+            ],
+        )
+        .unwrap();
+    }
+    tx.commit().unwrap();
+    println!("Imported {} courses for term {}.", allocations.len(), term);
+    Ok(())
+}
+
+pub fn add_override(
+    conn: &Connection,
+    term: &str,
+    code: &CourseCode,
+    allocation: Fixed,
+    reason: &str,
+) -> Result<(), String> {
+    let term_id = term_id(conn, term).ok_or(format!("Term {} isn't in the database.", term))?;
+    let course_ids: Vec<i64> = conn
+        .prepare("SELECT id FROM courses WHERE term_id = ?1 AND code = ?2")
+        .unwrap()
+        .query_map(params![term_id, code.to_string()], |row| row.get(0))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    // Rows kept separate with --duplicates=separate share a code, and there's no way to
+    // say which one is meant.
+    let course_id = match course_ids[..] {
+        [] => return Err(format!("Course {} isn't in term {}.", code, term)),
+        [course_id] => course_id,
+        _ => {
+            return Err(format!(
+                "Course {} is in term {} {} times, so it can't be overridden by code.",
+                code,
+                term,
+                course_ids.len()
+            ))
+        }
+    };
+    conn.execute(
+        "INSERT INTO overrides (course_id, allocation, reason, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![course_id, allocation.to_string(), reason, timestamp_now()],
+    )
+    .unwrap();
+    Ok(())
+}
+
+// Every term the course was offered, in the order the terms were imported.
+pub fn course_history(conn: &Connection, code: &CourseCode) -> Vec<HistoryEntry> {
+    let mut statement = conn
+        .prepare(
+            "SELECT terms.name, courses.name, courses.instructor, courses.enrollment,
+                 allocations.total, allocations.lab_amount, allocations.special_case,
+                 (SELECT allocation FROM overrides o WHERE o.course_id = courses.id
+                     ORDER BY o.id DESC LIMIT 1),
+                 (SELECT reason FROM overrides o WHERE o.course_id = courses.id
+                     ORDER BY o.id DESC LIMIT 1)
+             FROM courses
+             JOIN terms ON terms.id = courses.term_id
+             JOIN allocations ON allocations.course_id = courses.id
+             WHERE courses.code = ?1
+             ORDER BY terms.id, courses.position",
+        )
+        .unwrap();
+    statement
+        .query_map(params![code.to_string()], |row| {
+            let override_allocation = fixed_column(row, 7)?;
+            let override_reason: Option<String> = row.get(8)?;
+            Ok(HistoryEntry {
+                term: row.get(0)?,
+                name: row.get(1)?,
+                instructor: row.get(2)?,
+                enrollment: row.get(3)?,
+                total: fixed_column(row, 4)?.unwrap(),
+                lab_amount: fixed_column(row, 5)?.unwrap(),
+                special_case: row.get(6)?,
+                override_allocation: override_allocation
+                    .map(|a| (a, override_reason.unwrap_or_default())),
+            })
+        })
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+}
+
+pub fn print_history(code: &CourseCode, history: &[HistoryEntry]) {
+    if history.is_empty() {
+        println!("No terms in the database have {}.", code);
+        return;
+    }
+    println!("History of {}:", code);
+    for h in history {
+        let mut line = format!(
+            "{}: {} ({}), {} students: {} TAs [Lab: {:.2}, Lecture {:.2}]",
+            h.term,
+            h.name,
+            h.instructor,
+            h.enrollment,
+            h.total,
+            h.lab_amount,
+            h.total - h.lab_amount
+        );
+        if let Some(reason) = &h.special_case {
+            line.push_str(&format!("; special case: {}", reason));
+        }
+        if let Some((allocation, reason)) = &h.override_allocation {
+            line.push_str(&format!("; overridden to {} ({})", allocation, reason));
+        }
+        println!("{}", line);
+    }
+}
+
+// The same columns as the output of the run, plus any override of the allocation.
+pub fn export_term(conn: &Connection, term: &str, path: &str) -> Result<usize, String> {
+    let term_id = term_id(conn, term).ok_or(format!("Term {} isn't in the database.", term))?;
+    let rounding: String = conn
+        .query_row(
+            "SELECT rounding FROM terms WHERE id = ?1",
+            params![term_id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    let mut statement = conn
+        .prepare(
+            "SELECT courses.name, courses.instructor, courses.enrollment, allocations.total,
+                 allocations.lecture_amount, allocations.lab_amount, courses.multi_instructor,
+                 (SELECT allocation FROM overrides o WHERE o.course_id = courses.id
+                     ORDER BY o.id DESC LIMIT 1),
+                 (SELECT reason FROM overrides o WHERE o.course_id = courses.id
+                     ORDER BY o.id DESC LIMIT 1)
+             FROM courses
+             JOIN allocations ON allocations.course_id = courses.id
+             WHERE courses.term_id = ?1
+             ORDER BY courses.position",
+        )
+        .unwrap();
+    let rows: Vec<Vec<String>> = statement
+        .query_map(params![term_id], |row| {
+            Ok(vec![
+                row.get(0)?,
+                row.get(1)?,
+                row.get::<_, i32>(2)?.to_string(),
+                fixed_column(row, 3)?.unwrap().to_string(),
+                format!("{:.2}", fixed_column(row, 4)?.unwrap()),
+                fixed_column(row, 5)?.unwrap().to_string(),
+                rounding.clone(),
+                if row.get(6)? { "yes" } else { "no" }.to_string(),
+                fixed_column(row, 7)?
+                    .map(|a| a.to_string())
+                    .unwrap_or_default(),
+                row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+            ])
+        })
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    let mut wtr = csv::Writer::from_path(path).unwrap();
+    wtr.write_record([
+        "Course",
+        "Instructor",
+        "Enrollment",
+        "TA Allocation",
+        "Lecture Fraction",
+        "Lab Fraction",
+        "Rounding",
+        "Multi-Instructor",
+        "Override",
+        "Override Reason",
+    ])
+    .unwrap();
+    for row in &rows {
+        wtr.write_record(row).unwrap();
+    }
+    Ok(rows.len())
+}
+
+fn fixed_column(row: &Row, index: usize) -> rusqlite::Result<Option<Fixed>> {
+    let text: Option<String> = row.get(index)?;
+    Ok(text.map(|t| {
+        t.parse()
+            .unwrap_or_else(|e| panic!("{} in the database", e))
+    }))
+}
+
+fn term_id(conn: &Connection, term: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT id FROM terms WHERE name = ?1",
+        params![term],
+        |row| row.get(0),
+    )
+    .optional()
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::calculate_ta_hours_for_courses;
+    use crate::coursecode::CourseCode;
+    use crate::fixed::Fixed;
+    use crate::input::read_input_file;
+    use crate::manifest::{sha256_file, Manifest};
+    use crate::store::{add_override, course_history, export_term, import_term, open_database};
+    use crate::types::DuplicatePolicy;
+    use crate::write_output;
+    use serde_json::json;
+    use std::env;
+    use std::fs;
+
+    fn manifest(input_file: &str) -> Manifest {
+        Manifest {
+            crate_version: String::from("1.1.1"),
+            term: String::new(),
+            timestamp: String::from("2026-01-05T09:00:00Z"),
+            input_format: String::from("calculate"),
+            input_file: String::from(input_file),
+            input_sha256: sha256_file(input_file),
+            duplicate_policy: DuplicatePolicy::SEPARATE,
+            output_file: String::from("TA-Allocations.csv"),
+            output_sha256: String::new(),
+            configuration: json!({"ROUNDING_POLICY": "recorded"}),
+        }
+    }
+
+    fn temp_file(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("ta-store-{}-{}", std::process::id(), name))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn import_and_look_up_history() {
+        let mut conn = open_database(":memory:");
+        let w25 =
+            calculate_ta_hours_for_courses(read_input_file(&String::from("test_files/w25.csv")));
+        let w25_2 =
+            calculate_ta_hours_for_courses(read_input_file(&String::from("test_files/w25_2.csv")));
+
+        import_term(&mut conn, "W25", &manifest("test_files/w25.csv"), &w25).unwrap();
+        import_term(&mut conn, "S25", &manifest("test_files/w25_2.csv"), &w25_2).unwrap();
+        import_term(&mut conn, "F25", &manifest("test_files/w25.csv"), &w25).unwrap();
+        assert!(import_term(&mut conn, "W25", &manifest("test_files/w25.csv"), &w25).is_err());
+
+        let code = w25[0].0.code();
+        let history = course_history(&conn, &code);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].term, "W25");
+        assert_eq!(history[0].total, w25[0].1.total);
+        assert_eq!(history[0].lab_amount, w25[0].1.lab_amount);
+        assert_eq!(history[1].term, "F25");
+        assert!(history[0].override_allocation.is_none());
+
+        let stored: String = conn
+            .query_row(
+                "SELECT total FROM allocations ORDER BY course_id LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored, w25[0].1.total.to_string());
+
+        let configurations: i64 = conn
+            .query_row("SELECT COUNT(*) FROM configurations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(configurations, 1);
+        let (version, configuration): (String, String) = conn
+            .query_row(
+                "SELECT crate_version, configuration FROM terms
+                 JOIN configurations ON configurations.id = terms.configuration_id LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(version, "1.1.1");
+        assert_eq!(configuration, r#"{"ROUNDING_POLICY":"recorded"}"#);
+        assert!(course_history(&conn, &CourseCode::parse("ECE999").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn overrides_show_in_history() {
+        let mut conn = open_database(":memory:");
+        let w25 =
+            calculate_ta_hours_for_courses(read_input_file(&String::from("test_files/w25.csv")));
+        import_term(&mut conn, "W25", &manifest("test_files/w25.csv"), &w25).unwrap();
+        let code = w25[0].0.code();
+
+        add_override(&conn, "W25", &code, Fixed::new(3.0), "Extra section").unwrap();
+        add_override(&conn, "W25", &code, Fixed::new(3.5), "Two extra sections").unwrap();
+        assert!(add_override(&conn, "F25", &code, Fixed::new(1.0), "").is_err());
+        let missing = CourseCode::parse("ECE999").unwrap();
+        assert!(add_override(&conn, "W25", &missing, Fixed::new(1.0), "").is_err());
+
+        let history = course_history(&conn, &code);
+        assert_eq!(
+            history[0].override_allocation,
+            Some((Fixed::new(3.5), String::from("Two extra sections")))
+        );
+    }
+
+    #[test]
+    fn overrides_reject_codes_on_more_than_one_row() {
+        let mut conn = open_database(":memory:");
+        let mut w25 =
+            calculate_ta_hours_for_courses(read_input_file(&String::from("test_files/w25.csv")));
+        let mut second_section =
+            calculate_ta_hours_for_courses(read_input_file(&String::from("test_files/w25.csv")))
+                .remove(0);
+        second_section.0.instructor = String::from("Other Instructor");
+        w25.push(second_section);
+        import_term(&mut conn, "W25", &manifest("test_files/w25.csv"), &w25).unwrap();
+        let code = w25[0].0.code();
+
+        assert!(add_override(&conn, "W25", &code, Fixed::new(3.0), "Extra section").is_err());
+        let history = course_history(&conn, &code);
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|h| h.override_allocation.is_none()));
+    }
+
+    #[test]
+    fn export_matches_the_output_of_the_run() {
+        let mut conn = open_database(":memory:");
+        let w25 =
+            calculate_ta_hours_for_courses(read_input_file(&String::from("test_files/w25.csv")));
+        import_term(&mut conn, "W25", &manifest("test_files/w25.csv"), &w25).unwrap();
+        let output_file = temp_file("output.csv");
+        let export_file = temp_file("export.csv");
+        write_output(&output_file, &w25);
+
+        assert_eq!(export_term(&conn, "W25", &export_file), Ok(w25.len()));
+        assert!(export_term(&conn, "F25", &export_file).is_err());
+
+        let output = fs::read_to_string(&output_file).unwrap();
+        let export = fs::read_to_string(&export_file).unwrap();
+        fs::remove_file(&output_file).ok();
+        fs::remove_file(&export_file).ok();
+        let mut export_lines = export.lines();
+        assert_eq!(
+            export_lines.next().unwrap(),
+            format!(
+                "{},Override,Override Reason",
+                output.lines().next().unwrap()
+            )
+        );
+        for (output_line, export_line) in output.lines().skip(1).zip(export_lines) {
+            assert_eq!(export_line, format!("{},,", output_line));
+        }
+    }
+}
@@ -1,55 +1,317 @@
-#[derive(Debug)]
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Not every rule is used in the tables at any given time
+#[allow(dead_code)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
 pub enum CalculationRule {
     PER_TERM,
     PER_STUDENT,
     PER_LAB,
     PER_LEC_SECTION,
+    PER_TUT_SECTION,
 }
 
-#[derive(Eq, PartialEq, Debug)]
+// Which share of the allocation (lab or lecture) the hours count toward.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
 pub enum AllocationType {
     LAB,
-    NON_LAB,
     LECTURE,
 }
 
+// Which courses a rule is charged to. WITHOUT_LABS means no lab sections and not lab-only.
+// Not every condition is used in the tables at any given time
+#[allow(dead_code)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub enum Applicability {
+    ALWAYS,
+    WITH_LABS,
+    WITHOUT_LABS,
+    LAB_ONLY,
+    NOT_LAB_ONLY,
+    COURSE_TYPES(&'static [CourseType]),
+}
+
+#[derive(Serialize)]
 pub struct TAHourAllocation {
     pub name: &'static str,
-    pub hours: f32,
+    pub hours: Fixed,
     pub calc_rule: CalculationRule,
     pub alloc_type: AllocationType,
+    pub applies_to: Applicability,
+    // If set, the hours are for a course of UNIT_WEIGHT_BASELINE and scale with unit weight
+    pub scale_by_unit_weight: bool,
+    // Marking rules belong to an assessment, so they follow the course's assessment profile
+    pub assessment: Option<Assessment>,
+}
+
+// How the first-year support uplift is worked out: hours per student, hours per lecture
+// section, or a percentage of the hours from the rule table.
+// Only one is picked in the configuration at a time
+#[allow(dead_code)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub enum UpliftFormula {
+    PER_STUDENT(Fixed),
+    PER_SECTION(Fixed),
+    PERCENTAGE(Fixed),
+}
+
+// Extra hours on top of the rule table for courses that need more support. The hours are
+// for a course of UNIT_WEIGHT_BASELINE and always scale with the course's unit weight.
+#[derive(Serialize)]
+pub struct SupportUplift {
+    pub name: &'static str,
+    pub formula: UpliftFormula,
+    pub alloc_type: AllocationType,
+    pub applies_to: Applicability,
+    pub min_unit_weight: Fixed,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub enum Assessment {
+    MIDTERM,
+    FINAL,
+    ASSIGNMENT,
+}
+
+// How many of each assessment a course has. The marking rates in the rule tables are
+// written for STANDARD_ASSESSMENTS; other courses get those rules scaled or skipped.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub struct AssessmentProfile {
+    pub midterms: i32,
+    pub final_exam: bool,
+    pub assignments: i32,
+}
+
+impl AssessmentProfile {
+    pub fn count(&self, assessment: Assessment) -> i32 {
+        match assessment {
+            Assessment::MIDTERM => self.midterms,
+            Assessment::FINAL => self.final_exam as i32,
+            Assessment::ASSIGNMENT => self.assignments,
+        }
+    }
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum CourseType {
     FIRST_YEAR,
     UNDERGRAD,
     GRAD,
+    PROFESSIONAL_DEV,
+    MENG,
+}
+
+impl FromStr for CourseType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CourseType, String> {
+        match s.trim().to_ascii_uppercase().replace([' ', '-'], "_").as_str() {
+            "FIRST_YEAR" => Ok(CourseType::FIRST_YEAR),
+            "UNDERGRAD" => Ok(CourseType::UNDERGRAD),
+            "GRAD" => Ok(CourseType::GRAD),
+            "PROFESSIONAL_DEV" => Ok(CourseType::PROFESSIONAL_DEV),
+            "MENG" => Ok(CourseType::MENG),
+            _ => Err(format!(
+                "Unknown course type '{}' (expected FIRST_YEAR, UNDERGRAD, GRAD, PROFESSIONAL_DEV or MENG)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum DeliveryMode {
+    IN_PERSON,
+    ONLINE,
+    HYBRID,
+}
+
+impl FromStr for DeliveryMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<DeliveryMode, String> {
+        match s
+            .trim()
+            .to_ascii_uppercase()
+            .replace([' ', '-'], "_")
+            .as_str()
+        {
+            "IN_PERSON" => Ok(DeliveryMode::IN_PERSON),
+            "ONLINE" => Ok(DeliveryMode::ONLINE),
+            "HYBRID" => Ok(DeliveryMode::HYBRID),
+            _ => Err(format!(
+                "Unknown delivery mode '{}' (expected IN_PERSON, ONLINE or HYBRID)",
+                s
+            )),
+        }
+    }
+}
+
+// What to do with several rows for the same course code
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    ERROR,
+    MERGE,
+    SEPARATE,
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<DuplicatePolicy, String> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "ERROR" => Ok(DuplicatePolicy::ERROR),
+            "MERGE" => Ok(DuplicatePolicy::MERGE),
+            "SEPARATE" => Ok(DuplicatePolicy::SEPARATE),
+            _ => Err(format!(
+                "Unknown duplicate policy '{}' (expected error, merge or separate)",
+                s
+            )),
+        }
+    }
+}
+
+// Scales the named rule for courses delivered in the given mode; a factor of 0 skips it.
+#[derive(Serialize)]
+pub struct DeliveryModeAdjustment {
+    pub mode: DeliveryMode,
+    pub rule: &'static str,
+    pub factor: Fixed,
+    pub reason: &'static str,
 }
 
-#[derive(Debug)]
+// The optional fields can be left out of JSON, the same as leaving out their columns.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Course {
     pub name: String,
     pub instructor: String,
     pub enrollment: i32,
     pub lec_sections: i32,
     pub lab_sections: i32,
-    pub unit_weight: f32,
+    pub unit_weight: Fixed,
+    // From the optional Type column; if it's not given, the type comes from the course code
+    pub course_type: Option<CourseType>,
+    // From the optional Lab Only column
+    pub lab_only: Option<bool>,
+    // From the optional Lab Ratio and Lab Instructor columns
+    pub lab_students_per_ta: Option<Fixed>,
+    pub lab_instructor: Option<bool>,
+    // From the optional Tutorial Sections column
+    pub tut_sections: Option<i32>,
+    // From the optional Midterms, Final Exam and Assignments columns
+    pub midterms: Option<i32>,
+    pub final_exam: Option<bool>,
+    pub assignments: Option<i32>,
+    // From the optional Delivery Mode column; in person if it's not given
+    pub delivery_mode: Option<DeliveryMode>,
+    // Set when the course has more than one row in the input and they're kept separate.
+    // It's accepted in JSON, so that a course written out can be read back in, but what
+    // was there is ignored.
+    #[serde(deserialize_with = "ignore_multi_instructor")]
+    pub multi_instructor: bool,
 }
 
+fn ignore_multi_instructor<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    bool::deserialize(deserializer)?;
+    Ok(false)
+}
+
+pub struct LabParameters {
+    pub students_per_ta: Fixed,
+    pub students_per_ta_source: &'static str,
+    pub lab_instructor: bool,
+    pub lab_instructor_source: &'static str,
+}
+
+// Lab settings for a course that doesn't follow the usual students-per-TA ratio,
+// or that runs its labs without a lab instructor.
+#[derive(Serialize)]
+pub struct LabProfile {
+    pub course: &'static str,
+    pub reason: &'static str,
+    pub students_per_ta: Fixed,
+    pub lab_instructor: bool,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum LabOnlySource {
+    COLUMN,
+    LIST,
+    SUFFIX,
+    DEFAULT,
+}
+
+impl fmt::Display for LabOnlySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            LabOnlySource::COLUMN => "the Lab Only column",
+            LabOnlySource::LIST => "LAB_ONLY_COURSES",
+            LabOnlySource::SUFFIX => "the L suffix on the course code",
+            LabOnlySource::DEFAULT => "default",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+impl Course {
+    // Course names are checked when the input file is read, so this only fails for a
+    // Course built by hand with a bad name.
+    pub fn code(&self) -> CourseCode {
+        CourseCode::parse(&self.name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // Unless we're told otherwise, assume one tutorial per lecture section.
+    pub fn tutorial_sections(&self) -> i32 {
+        self.tut_sections.unwrap_or(self.lec_sections)
+    }
+
+    pub fn delivery_mode(&self) -> DeliveryMode {
+        self.delivery_mode.unwrap_or(DeliveryMode::IN_PERSON)
+    }
+
+    // Anything the input doesn't say comes from the standard profile.
+    pub fn assessment_profile(&self, standard: AssessmentProfile) -> AssessmentProfile {
+        AssessmentProfile {
+            midterms: self.midterms.unwrap_or(standard.midterms),
+            final_exam: self.final_exam.unwrap_or(standard.final_exam),
+            assignments: self.assignments.unwrap_or(standard.assignments),
+        }
+    }
+}
+
+#[derive(Serialize)]
 pub struct SpecialCase {
     pub course: &'static str,
     pub reason: &'static str,
     pub allocation_rule: AllocationRule,
-    pub allocation_amount: f32,
+    pub allocation_amount: Fixed,
 }
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct CourseAllocation {
-    pub total: f32,
-    pub lab_amount: f32,
+    pub total: Fixed,
+    pub lab_amount: Fixed,
+    pub components: Vec<AllocationComponent>,
+    // Set by check_for_special_case when the course is in SPECIAL_CASES
+    pub special_case_reason: Option<&'static str>,
+    // Everything calculate_ta_hours and check_for_special_case printed about the course
+    pub trace: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AllocationComponent {
+    pub name: &'static str,
+    pub hours: Fixed,
+    pub calc_rule: CalculationRule,
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Serialize)]
 pub enum AllocationRule {
     NO_TA_ALLOC,
     MIN_ALLOC,
@@ -58,3 +320,50 @@ pub enum AllocationRule {
     PER_LAB_SECTION,
     FIXED,
 }
+
+// Only one of each is picked in the configuration at a time
+#[allow(dead_code)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub enum RoundingMode {
+    NEAREST_QUARTER,
+    UP_TO_QUARTER,
+    NEAREST_TENTH,
+    NO_ROUNDING,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub enum RoundingScope {
+    TOTAL,
+    PER_COMPONENT,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize)]
+pub struct RoundingPolicy {
+    pub mode: RoundingMode,
+    pub scope: RoundingScope,
+}
+
+impl fmt::Display for RoundingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} on {:?}", self.mode, self.scope)
+    }
+}
+
+// A 120-student, one-lecture course worked out the way a run would, for the tests of
+// the modules that present allocations.
+#[cfg(test)]
+pub fn calculated(name: &str, instructor: &str) -> (Course, CourseAllocation) {
+    let c = Course {
+        name: String::from(name),
+        instructor: String::from(instructor),
+        enrollment: 120,
+        lec_sections: 1,
+        lab_sections: 0,
+        unit_weight: Fixed::new(0.5),
+        ..Default::default()
+    };
+    let allocation =
+        crate::calculator::check_for_special_case(&c, crate::calculator::calculate_ta_hours(&c));
+    (c, allocation)
+}
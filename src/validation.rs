@@ -0,0 +1,207 @@
+use crate::calculator::{check_course_type_column, determine_lab_only};
+use crate::configuration::{
+    MAX_STUDENTS_PER_LEC_SECTION, MAX_UNIT_WEIGHT, MIN_STUDENTS_PER_LAB_SECTION, MIN_UNIT_WEIGHT,
+};
+use crate::coursecode::CourseCode;
+use crate::fixed::Fixed;
+use crate::specialcases::SPECIAL_CASES;
+use crate::types::Course;
+
+// Checks the whole term's input for things that are probably mistakes, before any numbers
+// are worked out. Nothing here stops a run; it's up to whoever reads the warnings.
+pub fn validate(courses: &[Course]) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+    for c in courses {
+        warnings.extend(check_course(c));
+    }
+    warnings.extend(check_duplicate_codes(courses));
+    warnings.extend(check_special_cases(courses));
+    warnings
+}
+
+pub fn check_course(c: &Course) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+    if c.enrollment < 0 {
+        warnings.push(format!(
+            "Course {} has negative enrollment ({}).",
+            c.name, c.enrollment
+        ));
+    }
+    if c.lec_sections < 0 || c.lab_sections < 0 || c.tut_sections.is_some_and(|t| t < 0) {
+        warnings.push(format!(
+            "Course {} has a negative number of sections.",
+            c.name
+        ));
+    }
+    if c.unit_weight < MIN_UNIT_WEIGHT || c.unit_weight > MAX_UNIT_WEIGHT {
+        warnings.push(format!(
+            "Course {} has a unit weight of {}, outside {} to {}.",
+            c.name, c.unit_weight, MIN_UNIT_WEIGHT, MAX_UNIT_WEIGHT
+        ));
+    }
+
+    let (course_is_lab_only, _) = determine_lab_only(c);
+    if c.lec_sections == 0 && c.enrollment > 0 && !course_is_lab_only {
+        warnings.push(format!(
+            "Course {} has {} students but no lecture sections.",
+            c.name, c.enrollment
+        ));
+    }
+    if c.lec_sections > 0 {
+        let per_section = Fixed::from_int(c.enrollment) / Fixed::from_int(c.lec_sections);
+        if per_section > Fixed::from_int(MAX_STUDENTS_PER_LEC_SECTION) {
+            warnings.push(format!(
+                "Course {} has {:.0} students per lecture section (more than {}).",
+                c.name, per_section, MAX_STUDENTS_PER_LEC_SECTION
+            ));
+        }
+    }
+    if c.lab_sections > 0 {
+        let per_section = Fixed::from_int(c.enrollment) / Fixed::from_int(c.lab_sections);
+        if per_section < Fixed::from_int(MIN_STUDENTS_PER_LAB_SECTION) {
+            warnings.push(format!(
+                "Course {} has {} lab sections for {} students (fewer than {} per section).",
+                c.name, c.lab_sections, c.enrollment, MIN_STUDENTS_PER_LAB_SECTION
+            ));
+        }
+    }
+    warnings.extend(check_course_type_column(c));
+    warnings
+}
+
+pub fn check_duplicate_codes(courses: &[Course]) -> Vec<String> {
+    let mut seen: Vec<(CourseCode, Vec<&str>)> = Vec::new();
+    for c in courses {
+        let code = c.code();
+        match seen.iter_mut().find(|(s, _)| *s == code) {
+            Some((_, names)) => names.push(&c.name),
+            None => seen.push((code, vec![&c.name])),
+        }
+    }
+    seen.into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(code, names)| {
+            format!(
+                "Course {} appears {} times in the input ({}).",
+                code,
+                names.len(),
+                names.join(", ")
+            )
+        })
+        .collect()
+}
+
+// A special case that matches nothing is usually a course that isn't offered this term,
+// but it can also be a typo in SPECIAL_CASES. An entry whose code doesn't parse, or that
+// means the same course as an earlier entry, is always a typo.
+pub fn check_special_cases(courses: &[Course]) -> Vec<String> {
+    let codes: Vec<CourseCode> = courses.iter().map(Course::code).collect();
+    let mut warnings: Vec<String> = Vec::new();
+    let mut seen: Vec<CourseCode> = Vec::new();
+    for sc in SPECIAL_CASES {
+        match CourseCode::parse(sc.course) {
+            Ok(code) if seen.contains(&code) => warnings.push(format!(
+                "Special case for {} ({}) is for the same course as an earlier one.",
+                sc.course, sc.reason
+            )),
+            Ok(code) => {
+                if !codes.contains(&code) {
+                    warnings.push(format!(
+                        "Special case for {} ({}) doesn't match any course in the input.",
+                        sc.course, sc.reason
+                    ));
+                }
+                seen.push(code);
+            }
+            Err(e) => warnings.push(format!(
+                "Special case for {} ({}) has a bad course code: {}",
+                sc.course, sc.reason, e
+            )),
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixed::Fixed;
+    use crate::input::read_input_file;
+    use crate::specialcases::SPECIAL_CASES;
+    use crate::types::Course;
+    use crate::validation::{check_course, check_duplicate_codes, check_special_cases};
+
+    fn course(name: &str, enrollment: i32, lec_sections: i32, lab_sections: i32) -> Course {
+        Course {
+            name: String::from(name),
+            enrollment,
+            lec_sections,
+            lab_sections,
+            unit_weight: Fixed::new(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plausible_course_has_no_warnings() {
+        assert!(check_course(&course("ECE 250", 120, 1, 3)).is_empty());
+    }
+
+    #[test]
+    fn implausible_courses_are_flagged() {
+        assert_eq!(check_course(&course("ECE 250", -5, 1, 0)).len(), 1);
+        assert_eq!(check_course(&course("ECE 250", 300, 0, 0)).len(), 1);
+        assert_eq!(check_course(&course("ECE 250", 40, 1, 50)).len(), 1);
+        assert_eq!(check_course(&course("ECE 250", 600, 1, 0)).len(), 1);
+
+        let heavy = Course {
+            unit_weight: Fixed::new(5.0),
+            ..course("ECE 250", 120, 1, 0)
+        };
+        assert_eq!(
+            check_course(&heavy),
+            vec!["Course ECE 250 has a unit weight of 5, outside 0.25 to 1.5."]
+        );
+    }
+
+    #[test]
+    fn lab_only_course_needs_no_lecture_sections() {
+        assert!(check_course(&course("NE 216L", 60, 0, 2)).is_empty());
+    }
+
+    #[test]
+    fn duplicate_codes_are_found_after_normalizing() {
+        let courses = vec![
+            course("ECE 150", 200, 1, 0),
+            course("ECE 250", 100, 1, 0),
+            course("ece150", 200, 1, 0),
+        ];
+
+        assert_eq!(
+            check_duplicate_codes(&courses),
+            vec!["Course ECE150 appears 2 times in the input (ECE 150, ece150)."]
+        );
+    }
+
+    #[test]
+    fn special_cases_missing_from_input_are_reported() {
+        // ECE 459 is in the file, the capstones aren't
+        let courses = read_input_file(&String::from("test_files/delivery_modes.csv"));
+        let warnings = check_special_cases(&courses);
+
+        assert!(warnings.contains(&String::from(
+            "Special case for ECE498A (Capstone Course) doesn't match any course in the input."
+        )));
+        assert!(!warnings.iter().any(|w| w.contains("ECE459")));
+    }
+
+    #[test]
+    fn configured_special_cases_have_no_typos() {
+        // With every configured course in the input, only typos would be left
+        let courses: Vec<Course> = SPECIAL_CASES
+            .iter()
+            .map(|sc| course(sc.course, 100, 1, 0))
+            .collect();
+
+        assert!(check_special_cases(&courses).is_empty());
+    }
+}